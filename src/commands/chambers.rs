@@ -0,0 +1,77 @@
+use super::{
+    Api, Command, CommandOutcome, Context, Group, gun_name, no_such_gun, paused, render, reply,
+    topic_id,
+};
+use crate::{Roulette, locale::MessageKey};
+use frankenstein::types::Message;
+use tokio::sync::Mutex;
+
+/// Emoji of a chamber fired in the current loading.
+const FIRED: &str = "🔘";
+/// Emoji of a chamber left, whose contents are unknown.
+const UNKNOWN: &str = "⬛";
+
+/// Show the chambers of the revolver, telling the fired ones without revealing the loaded ones.
+pub struct ChambersCommand;
+
+impl Command for ChambersCommand {
+    const TRIGGER: &'static str = "chambers";
+    const HELP: &'static str = "Show which chambers of the revolver have been fired.";
+    const INFORMATIONAL: bool = true;
+    async fn execute(
+        _context: &Context<impl Api>,
+        msg: Message,
+        arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let gun = gun_name(arg);
+        let mut group = group.lock().await;
+        if !group.has_gun(gun) {
+            return reply(no_such_gun(&group, gun));
+        }
+        let Some(roulette) = group.playable_gun(topic_id(&msg), gun) else {
+            return reply(paused(&group));
+        };
+        reply(render(
+            roulette.config().message(MessageKey::Cylinder),
+            &[("chambers", &render_chambers(roulette))],
+        ))
+    }
+}
+
+/// Render each chamber as an emoji, the fired ones first, then the left ones as in [`Roulette::peek`].
+fn render_chambers(roulette: &Roulette) -> String {
+    let (_, chambers) = roulette.info();
+    let (_, left) = roulette.peek();
+    FIRED.repeat(chambers - left) + &UNKNOWN.repeat(left)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RouletteConfig;
+
+    #[test]
+    fn test_render_chambers() {
+        let config = RouletteConfig {
+            chambers: 6,
+            bullets: 2,
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        let mut roulette = config.clone().start().unwrap();
+        assert_eq!(render_chambers(&roulette), "⬛⬛⬛⬛⬛⬛");
+        roulette.fire();
+        roulette.fire();
+        let rendered = render_chambers(&roulette);
+        assert_eq!(rendered.chars().count(), 6);
+        assert_eq!(rendered, "🔘🔘⬛⬛⬛⬛");
+
+        // Any loading renders alike at the same position, revealing no bullets
+        for seed in 0..10 {
+            let mut other = Roulette::from_seed(config.clone(), seed).unwrap();
+            other.position = 2;
+            assert_eq!(render_chambers(&other), rendered);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use super::{Api, Command, CommandOutcome, Context, Group, display_name, message, render, reply};
+use crate::locale::MessageKey;
+use frankenstein::types::Message;
+use log::{error, info};
+use tokio::sync::Mutex;
+
+/// Back out of a pending pull, at the cost of some reputation.
+pub struct ChickenCommand;
+
+impl Command for ChickenCommand {
+    const TRIGGER: &'static str = "chicken";
+    const HELP: &'static str = "Back out of a pending pull, at the cost of some reputation.";
+    async fn execute(
+        _context: &Context<impl Api>,
+        msg: Message,
+        _arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let Some(sender) = &msg.from else {
+            error!("Cannot determine sender of message: {msg:?}");
+            return Vec::new();
+        };
+        let name = display_name(sender);
+        let mut group = group.lock().await;
+        match group.chicken_out(sender.id) {
+            Some(count) => {
+                info!(
+                    "User {name} chickened out in group <{}>, {count} times so far",
+                    msg.chat.id
+                );
+                reply(render(
+                    group.roulette().config().message(MessageKey::ChickenedOut),
+                    &[("name", &name), ("count", &count.to_string())],
+                ))
+            }
+            None => reply(message(&group, MessageKey::NoPendingPull)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        RouletteConfig,
+        commands::RouletteCommand,
+        mock::{MockApi, context, message},
+    };
+    use std::time::Duration;
+    use tokio::time::sleep;
+
+    #[tokio::test]
+    async fn test_chicken_out() {
+        let config = RouletteConfig {
+            chambers: 1,
+            bullets: 1,
+            jam_probability: 0.0,
+            chicken_window_secs: 1,
+            ..Default::default()
+        };
+        let group = Mutex::new(Group::new(config.start().unwrap()));
+        let context = context(MockApi::default().with_member("member").with_sending());
+
+        // Backing out within the window, so that the pull never happens
+        let (pulled, chickened) = tokio::join!(
+            RouletteCommand::execute(&context, message("/roulette"), "", &group),
+            async {
+                sleep(Duration::from_millis(100)).await;
+                ChickenCommand::execute(&context, message("/chicken"), "", &group).await
+            }
+        );
+        assert!(pulled.is_empty(), "{pulled:?}");
+        assert_eq!(
+            chickened,
+            reply("Ferris chickened out! That's 1 times so far.".to_string())
+        );
+        assert_eq!(group.lock().await.stats().fires, 0);
+        let prompt = &context.bot.requests("sendMessage")[0];
+        assert_eq!(
+            prompt["text"],
+            "Ferris raises the revolver... Send /chicken within 1s to back out."
+        );
+        assert_eq!(prompt["reply_parameters"]["message_id"], 1);
+
+        // Nothing to back out of any more, so the next pull goes off after the window
+        let outcomes = ChickenCommand::execute(&context, message("/chicken"), "", &group).await;
+        assert_eq!(
+            outcomes,
+            reply("You have no pending pull to back out of.".to_string())
+        );
+        let outcomes = RouletteCommand::execute(&context, message("/roulette"), "", &group).await;
+        assert!(
+            matches!(outcomes.first(), Some(CommandOutcome::Restrict { .. })),
+            "{outcomes:?}"
+        );
+        assert_eq!(group.lock().await.stats().fires, 1);
+    }
+}
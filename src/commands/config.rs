@@ -0,0 +1,30 @@
+use super::{Api, Command, CommandOutcome, Context, Group, is_admin, message, reply};
+use crate::locale::MessageKey;
+use frankenstein::types::Message;
+use tokio::sync::Mutex;
+
+/// Show the resolved configuration of the game in this group.
+pub struct ConfigCommand;
+
+impl Command for ConfigCommand {
+    const TRIGGER: &'static str = "config";
+    const HELP: &'static str = "Show the configuration of the game in this group. Admin only.";
+    const INFORMATIONAL: bool = true;
+    async fn execute(
+        context: &Context<impl Api>,
+        msg: Message,
+        _arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let bot = context.bot.as_ref();
+        let Some(admin) = is_admin(bot, &msg).await else {
+            return Vec::new();
+        };
+        if !admin {
+            return reply(message(&*group.lock().await, MessageKey::ConfigAdminOnly));
+        }
+        let group = group.lock().await;
+        let config = group.roulette().config();
+        reply(format!("Configuration of this group:\n{config}"))
+    }
+}
@@ -0,0 +1,123 @@
+use super::{
+    Api, Command, CommandOutcome, Context, Group, gun_name, is_admin, no_such_gun, reply, topic_id,
+};
+use crate::{Roulette, locale::MessageKey};
+use frankenstein::{methods::SendMessageParams, types::Message};
+use log::error;
+use tokio::sync::Mutex;
+
+/// Dump the full state of the revolver, including the loaded chambers, for checking game balance.
+///
+/// The dump is sent privately to the admin, so that players in the group never see it.
+pub struct DebugCommand;
+
+impl Command for DebugCommand {
+    const TRIGGER: &'static str = "debug";
+    const HELP: &'static str =
+        "Dump the loading of the revolver, for checking game balance. Admin only, if enabled.";
+    const INFORMATIONAL: bool = true;
+    async fn execute(
+        context: &Context<impl Api>,
+        msg: Message,
+        arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let bot = context.bot.as_ref();
+        let config = group.lock().await.roulette().config().clone();
+        if !config.debug_enabled() {
+            return reply(config.message(MessageKey::DebugDisabled).to_string());
+        }
+        // Checked before locking the group again, so as not to hold it across the request
+        let Some(admin) = is_admin(bot, &msg).await else {
+            return Vec::new();
+        };
+        if !admin {
+            return reply(config.message(MessageKey::DebugAdminOnly).to_string());
+        }
+        let Some(sender) = &msg.from else {
+            return Vec::new();
+        };
+        let gun = gun_name(arg);
+        let text = {
+            let group = group.lock().await;
+            let Some(roulette) = group.gun(topic_id(&msg), gun) else {
+                return reply(no_such_gun(&group, gun));
+            };
+            debug_text(roulette)
+        };
+        let send_message_param = SendMessageParams::builder()
+            .chat_id(sender.id as i64)
+            .text(text)
+            .build();
+        if let Err(err) = bot.send_message(&send_message_param).await {
+            error!("Failed to send the debug dump: {err}");
+            return reply(config.message(MessageKey::DebugFailed).to_string());
+        }
+        reply(config.message(MessageKey::DebugSent).to_string())
+    }
+}
+
+/// Dump the state of the revolver, marking loaded chambers with `1` and empty ones with `0`.
+fn debug_text(roulette: &Roulette) -> String {
+    let state = roulette.snapshot();
+    let contents: String = state
+        .contents
+        .iter()
+        .map(|&loaded| if loaded { '1' } else { '0' })
+        .collect();
+    let stats = state.stats;
+    format!(
+        "Contents: {contents}\nPosition: {}\nSeed: {}\nStats: {} fires, {} bullets, {} jams, {} reloads",
+        state.position, state.seed, stats.fires, stats.bullets, stats.jams, stats.reloads
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        RouletteConfig,
+        mock::{MockApi, SENDER, context, message},
+    };
+
+    #[tokio::test]
+    async fn test_debug() {
+        let msg = message("/debug");
+        let api = |status| MockApi::default().with_member(status);
+        let config = RouletteConfig {
+            chambers: 2,
+            bullets: 2,
+            ..Default::default()
+        };
+        let debug = async |config: &RouletteConfig, status| {
+            let group = Mutex::new(Group::new(config.clone().start().unwrap()));
+            DebugCommand::execute(&context(api(status)), msg.clone(), "", &group).await
+        };
+        let reply_text = |text: &str| vec![CommandOutcome::Reply(text.to_string())];
+
+        // Disabled by default, even for admins
+        let outcomes = debug(&config, "creator").await;
+        assert_eq!(outcomes, reply_text("Debugging is disabled in this group"));
+
+        let enabled = RouletteConfig {
+            debug_enabled: true,
+            ..config
+        };
+        let outcomes = debug(&enabled, "member").await;
+        assert_eq!(outcomes, reply_text("Only admins can debug the revolver"));
+        // Told in private, not in the group
+        let outcomes = debug(&enabled, "creator").await;
+        assert_eq!(
+            outcomes,
+            reply_text("Failed to send you the dump privately, please start a chat with me first")
+        );
+        let group = Mutex::new(Group::new(enabled.start().unwrap()));
+        let admin = context(api("creator").with_sending());
+        let outcomes = DebugCommand::execute(&admin, msg, "", &group).await;
+        assert_eq!(outcomes, reply_text("Sent you the dump privately"));
+        let sent = &admin.bot.requests("sendMessage")[0];
+        assert_eq!(sent["chat_id"], SENDER);
+        let text = sent["text"].as_str().unwrap();
+        assert!(text.starts_with("Contents: 11\nPosition: 0\n"), "{text}");
+    }
+}
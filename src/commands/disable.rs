@@ -0,0 +1,33 @@
+use super::{Api, Command, CommandOutcome, Context, Group, is_admin, message, reply};
+use crate::locale::MessageKey;
+use frankenstein::types::Message;
+use tokio::sync::Mutex;
+
+/// Pause the game, until resumed by [`EnableCommand`](super::enable::EnableCommand).
+pub struct DisableCommand;
+
+impl Command for DisableCommand {
+    const TRIGGER: &'static str = "disable";
+    const HELP: &'static str = "Pause the game. Admin only.";
+    async fn execute(
+        context: &Context<impl Api>,
+        msg: Message,
+        _arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let bot = context.bot.as_ref();
+        let Some(admin) = is_admin(bot, &msg).await else {
+            return Vec::new();
+        };
+        if !admin {
+            return reply(message(&*group.lock().await, MessageKey::DisableAdminOnly));
+        }
+        let mut group = group.lock().await;
+        let key = if group.set_enabled(false) {
+            MessageKey::Disabled
+        } else {
+            MessageKey::AlreadyDisabled
+        };
+        reply(message(&group, key))
+    }
+}
@@ -0,0 +1,33 @@
+use super::{Api, Command, CommandOutcome, Context, Group, is_admin, message, reply};
+use crate::locale::MessageKey;
+use frankenstein::types::Message;
+use tokio::sync::Mutex;
+
+/// Resume the game paused by [`DisableCommand`](super::disable::DisableCommand).
+pub struct EnableCommand;
+
+impl Command for EnableCommand {
+    const TRIGGER: &'static str = "enable";
+    const HELP: &'static str = "Resume the paused game. Admin only.";
+    async fn execute(
+        context: &Context<impl Api>,
+        msg: Message,
+        _arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let bot = context.bot.as_ref();
+        let Some(admin) = is_admin(bot, &msg).await else {
+            return Vec::new();
+        };
+        if !admin {
+            return reply(message(&*group.lock().await, MessageKey::EnableAdminOnly));
+        }
+        let mut group = group.lock().await;
+        let key = if group.set_enabled(true) {
+            MessageKey::Enabled
+        } else {
+            MessageKey::AlreadyEnabled
+        };
+        reply(message(&group, key))
+    }
+}
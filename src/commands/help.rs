@@ -0,0 +1,59 @@
+use super::{Api, Command, CommandOutcome, Context, Group, Triggers, reply};
+use frankenstein::types::Message;
+use tokio::sync::Mutex;
+
+/// List the available commands.
+pub struct HelpCommand;
+
+impl Command for HelpCommand {
+    const TRIGGER: &'static str = "help";
+    const HELP: &'static str = "List the available commands.";
+    const INFORMATIONAL: bool = true;
+    async fn execute(
+        context: &Context<impl Api>,
+        _msg: Message,
+        _arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let triggers = &context.triggers();
+        let debug = group.lock().await.roulette().config().debug_enabled();
+        reply(help_text(triggers, debug))
+    }
+}
+
+/// Compose the help text from the same list of commands registered with Telegram, with `/debug`
+/// only if enabled.
+fn help_text(triggers: &Triggers, debug: bool) -> String {
+    let mut text = "Available commands:".to_string();
+    for command in triggers.list(debug) {
+        text += &format!("\n/{} - {}", command.command, command.description);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_help_text() {
+        let triggers = Triggers::default();
+        let text = help_text(&triggers, true);
+        for command in triggers.list(true) {
+            let trigger = format!("/{}", command.command);
+            assert!(text.contains(&trigger), "missing {trigger}");
+            // Every listed command can actually be triggered
+            assert!(triggers.parse(Some(&trigger), "bot").is_some());
+        }
+        assert!(text.contains("/help - List the available commands."));
+        assert!(text.contains("/debug - "));
+        assert!(!help_text(&triggers, false).contains("/debug"));
+
+        // Listed under the remapped triggers
+        let remapped = HashMap::from([("help".to_string(), "commands".to_string())]);
+        let text = help_text(&Triggers::new(&remapped).unwrap(), false);
+        assert!(text.contains("/commands - List the available commands."));
+        assert!(!text.contains("/help"));
+    }
+}
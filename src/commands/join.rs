@@ -0,0 +1,55 @@
+use super::{
+    Api, Command, CommandOutcome, Context, Group, display_name, get_member, is_admin_member,
+    is_current_member, message, render, reply,
+};
+use crate::{locale::MessageKey, tournament::JoinError};
+use frankenstein::types::Message;
+use log::error;
+use tokio::sync::Mutex;
+
+/// Join the ongoing tournament.
+pub struct JoinCommand;
+
+impl Command for JoinCommand {
+    const TRIGGER: &'static str = "join";
+    const HELP: &'static str = "Join the ongoing tournament.";
+    async fn execute(
+        context: &Context<impl Api>,
+        msg: Message,
+        _arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let bot = context.bot.as_ref();
+        let Some(sender) = &msg.from else {
+            error!("Cannot determine sender of message: {msg:?}");
+            return Vec::new();
+        };
+        let Some(member) = get_member(bot, &msg).await else {
+            return Vec::new();
+        };
+        if is_admin_member(&member) {
+            return reply(message(&*group.lock().await, MessageKey::JoinAdminOnly));
+        }
+        if !is_current_member(&member) {
+            return reply(message(&*group.lock().await, MessageKey::NotMember));
+        }
+        let name = display_name(sender);
+        let mut group = group.lock().await;
+        let config = group.roulette().config().clone();
+        let Some(tournament) = group.tournament() else {
+            return reply(config.message(MessageKey::NoTournament).to_string());
+        };
+        let text = match tournament.join(sender.id, name.clone()) {
+            Ok(count) => render(
+                config.message(MessageKey::Joined),
+                &[("name", &name), ("count", &count.to_string())],
+            ),
+            Err(JoinError::AlreadyJoined) => render(
+                config.message(MessageKey::AlreadyJoined),
+                &[("name", &name)],
+            ),
+            Err(JoinError::Closed) => config.message(MessageKey::JoinClosed).to_string(),
+        };
+        reply(text)
+    }
+}
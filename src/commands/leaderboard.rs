@@ -0,0 +1,85 @@
+use super::{Api, Command, CommandOutcome, Context, Group, message, render, reply};
+use crate::{constants::LEADERBOARD_SIZE, group::UserStats, locale::MessageKey};
+use frankenstein::types::Message;
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+/// Show the unluckiest players in this group.
+pub struct LeaderboardCommand;
+
+impl Command for LeaderboardCommand {
+    const TRIGGER: &'static str = "leaderboard";
+    const HELP: &'static str = "Show the unluckiest players in this group.";
+    const INFORMATIONAL: bool = true;
+    async fn execute(
+        _context: &Context<impl Api>,
+        _msg: Message,
+        _arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let group = group.lock().await;
+        let ranking = unluckiest(group.user_stats(), LEADERBOARD_SIZE);
+        if ranking.is_empty() {
+            return reply(message(&group, MessageKey::NobodyShot));
+        }
+        let config = group.roulette().config();
+        let mut text = config.message(MessageKey::Leaderboard).to_string();
+        for (rank, stats) in ranking.iter().enumerate() {
+            text.push('\n');
+            text += &render(
+                config.message(MessageKey::LeaderboardEntry),
+                &[
+                    ("rank", &(rank + 1).to_string()),
+                    ("name", &stats.name),
+                    ("shot", &stats.shot.to_string()),
+                    ("survived", &stats.survived.to_string()),
+                ],
+            );
+        }
+        reply(text)
+    }
+}
+
+/// Rank users who have been shot by the number of times, the fewer survivals the unluckier, taking at most `count` users.
+fn unluckiest(user_stats: &HashMap<u64, UserStats>, count: usize) -> Vec<&UserStats> {
+    let mut ranking: Vec<_> = user_stats.values().filter(|stats| stats.shot > 0).collect();
+    ranking.sort_by(|a, b| {
+        b.shot
+            .cmp(&a.shot)
+            .then(a.survived.cmp(&b.survived))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    ranking.truncate(count);
+    ranking
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unluckiest() {
+        let user = |name: &str, shot, survived| UserStats {
+            name: name.to_string(),
+            shot,
+            survived,
+        };
+        let user_stats = HashMap::from([
+            (1, user("alice", 1, 5)),
+            (2, user("bob", 3, 0)),
+            (3, user("carol", 1, 2)),
+            (4, user("dave", 0, 9)),
+            (5, user("erin", 1, 2)),
+        ]);
+
+        let names = |count| -> Vec<_> {
+            unluckiest(&user_stats, count)
+                .iter()
+                .map(|stats| stats.name.as_str())
+                .collect()
+        };
+        // Never shot users are excluded, and ties are broken by name
+        assert_eq!(names(10), ["bob", "carol", "erin", "alice"]);
+        assert_eq!(names(2), ["bob", "carol"]);
+    }
+}
@@ -1,14 +1,207 @@
+mod chambers;
+mod chicken;
+mod config;
+mod debug;
+mod disable;
+mod enable;
+mod help;
+mod join;
+mod leaderboard;
+mod odds;
+mod pardon;
 mod peek;
+mod reload;
 mod roulette;
+mod seed;
+mod spin;
+mod stats;
+mod status;
+mod surrender;
+mod tournament;
 
-use super::Roulette;
+use super::{Group, Roulette, group::DEFAULT_GUN, locale::MessageKey, update::Context};
+use crate::constants::{MAX_NAME_LENGTH, RESTRICTED_PERM, UNRESTRICTED_PERM};
+use chambers::ChambersCommand;
+use chicken::ChickenCommand;
+use config::ConfigCommand;
+use debug::DebugCommand;
+use disable::DisableCommand;
+use enable::EnableCommand;
 use frankenstein::{
-    client_reqwest::Bot,
-    types::{BotCommand, Message},
+    AsyncTelegramApi, Error,
+    methods::{GetChatMemberParams, GetChatParams, RestrictChatMemberParams},
+    types::{BotCommand, ChatMember, ChatPermissions, Message, User},
 };
+use help::HelpCommand;
+use jiff::{Timestamp, tz::TimeZone};
+use join::JoinCommand;
+use leaderboard::LeaderboardCommand;
+use log::{error, info};
+use odds::OddsCommand;
+use pardon::PardonCommand;
 use peek::PeekCommand;
+use reload::ReloadCommand;
 use roulette::RouletteCommand;
+use seed::SeedCommand;
+use spin::SpinCommand;
+use stats::StatsCommand;
+use status::StatusCommand;
+use std::collections::HashMap;
+use surrender::SurrenderCommand;
 use tokio::sync::Mutex;
+use tournament::TournamentCommand;
+
+/// Get the text for the key in the language of the group.
+fn message(group: &Group, key: MessageKey) -> String {
+    group.roulette().config().message(key).to_string()
+}
+
+/// Reply when the game is paused.
+fn paused(group: &Group) -> String {
+    message(group, MessageKey::Paused)
+}
+
+/// Get the ID of the forum topic the message is in, if any.
+pub(crate) fn topic_id(msg: &Message) -> Option<i32> {
+    // Replies in groups without topics carry the thread of the replied message too
+    msg.message_thread_id
+        .filter(|_| msg.is_topic_message == Some(true))
+}
+
+/// Get the gun named by the argument, defaulting to [`DEFAULT_GUN`].
+fn gun_name(arg: &str) -> &str {
+    if arg.is_empty() { DEFAULT_GUN } else { arg }
+}
+
+/// Render the template, substituting placeholders like `{name}` with the given values.
+///
+/// Unknown placeholders are left as is. Substituted values are never rendered again, so that user names can't inject placeholders.
+pub(crate) fn render(template: &str, values: &[(&str, &str)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            let key = &after[..end];
+            values
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, value)| (*value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                rendered.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = after;
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Compose the notice that the gun has been reloaded.
+fn reloaded_reply(roulette: &Roulette) -> String {
+    let (bullets, chambers) = roulette.info();
+    render(
+        roulette.config().message(MessageKey::Reloaded),
+        &[
+            ("bullets", &bullets.to_string()),
+            ("chambers", &chambers.to_string()),
+        ],
+    )
+}
+
+/// Reload the gun if empty, returning the tip to append to the reply, and the standalone announcement if configured instead.
+///
+/// With `auto_reload` disabled, the gun stays empty and the tip tells so instead. With
+/// `show_reload_tip` disabled, the tip is left out, though the announcement is kept.
+fn reload_if_empty(roulette: &mut Roulette, triggers: &Triggers) -> (String, Option<String>) {
+    if !roulette.is_empty() {
+        return (String::new(), None);
+    }
+    let show_tip = roulette.config().show_reload_tip();
+    let tip = |text: &str| {
+        if show_tip {
+            format!(" {text}")
+        } else {
+            String::new()
+        }
+    };
+    if !roulette.config().auto_reload() {
+        let emptied = render(
+            roulette.config().message(MessageKey::Emptied),
+            &[("reload", &triggers.command(Commands::Reload))],
+        );
+        return (tip(&emptied), None);
+    }
+    roulette.reload();
+    let text = reloaded_reply(roulette);
+    if roulette.config().announce_reload() {
+        (String::new(), Some(text))
+    } else {
+        (tip(&text), None)
+    }
+}
+
+/// Reply when there's no gun with the given name.
+fn no_such_gun(group: &Group, name: &str) -> String {
+    render(
+        group.roulette().config().message(MessageKey::NoSuchGun),
+        &[
+            ("name", &sanitize_name(name)),
+            ("guns", &group.gun_names().join(", ")),
+        ],
+    )
+}
+
+/// An action to take as the outcome of a command, carried out after the command returns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandOutcome {
+    /// Reply to the command message.
+    Reply(String),
+    /// Reply to the command message, kept even if the command is informational, as it tells of a change to the game.
+    LastingReply(String),
+    /// Send a standalone message to the chat, so that everyone notices.
+    Send(String),
+    /// Send a standalone message to the chat and pin it, e.g. for a jackpot.
+    SendPinned(String),
+    /// React to the command message with the emoji.
+    React(String),
+    /// Mute the user until the Unix timestamp.
+    Restrict {
+        /// ID of the user.
+        user_id: u64,
+        /// Name of the user, for logging.
+        name: String,
+        /// Unix timestamp until which the user is muted.
+        until: u64,
+    },
+    /// Unmute the user.
+    Unrestrict {
+        /// ID of the user.
+        user_id: u64,
+        /// Name of the user, for logging.
+        name: String,
+    },
+}
+
+/// Reply to the command message, without doing anything else.
+fn reply(text: String) -> Vec<CommandOutcome> {
+    vec![CommandOutcome::Reply(text)]
+}
+
+/// The Telegram API as used by the bot, implemented by [`Bot`](frankenstein::client_reqwest::Bot).
+///
+/// Commands and the [`Context`](crate::update::Context) accept any implementation, which is the seam
+/// for testing them end-to-end against a mock of Telegram that records the requests.
+pub trait Api: AsyncTelegramApi<Error = Error> + Send + Sync {}
+
+impl<T: AsyncTelegramApi<Error = Error> + Send + Sync> Api for T {}
 
 /// A command.
 pub trait Command {
@@ -16,30 +209,169 @@ pub trait Command {
     const TRIGGER: &'static str;
     /// Help message.
     const HELP: &'static str;
-    /// Execute the command.
-    async fn execute(bot: &Bot, msg: Message, roulette: &Mutex<Roulette>) -> Option<String>;
+    /// Whether the reply is purely informational, thus may be deleted after a while.
+    const INFORMATIONAL: bool = false;
+    /// Execute the command, with the text after the trigger as its argument (empty if absent), returning the actions to take in order.
+    ///
+    /// The `context` is for talking to Telegram and for mentioning other commands under its triggers.
+    async fn execute(
+        context: &Context<impl Api>,
+        msg: Message,
+        arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome>;
 }
 
-/// List of commands. Cheap to clone.
-#[non_exhaustive]
-pub enum Commands {
-    Peek,
-    Roulette,
+/// Define [`Commands`] from a single list of variants and the commands they dispatch to, so that
+/// every command is parsed, executed and listed alike.
+macro_rules! commands {
+    ($($variant:ident => $command:ident),* $(,)?) => {
+        /// List of commands. Cheap to clone.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #[non_exhaustive]
+        pub enum Commands {
+            $($variant,)*
+        }
+
+        impl Commands {
+            /// All commands, in the order they are listed.
+            pub const ALL: &[Self] = &[$(Self::$variant),*];
+
+            /// Get the canonical name of the command, i.e. its default trigger word.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $command::TRIGGER,)*
+                }
+            }
+
+            /// Get the help message of the command.
+            pub fn help(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $command::HELP,)*
+                }
+            }
+
+            /// Whether the reply of the command is purely informational.
+            pub fn is_informational(&self) -> bool {
+                match self {
+                    $(Self::$variant => $command::INFORMATIONAL,)*
+                }
+            }
+
+            /// Execute the command, returning the actions to take in order.
+            pub async fn execute(
+                &self,
+                context: &Context<impl Api>,
+                msg: Message,
+                arg: &str,
+                group: &Mutex<Group>,
+            ) -> Vec<CommandOutcome> {
+                match self {
+                    $(Self::$variant => $command::execute(context, msg, arg, group).await,)*
+                }
+            }
+        }
+    };
+}
+
+commands! {
+    Chambers => ChambersCommand,
+    Chicken => ChickenCommand,
+    Config => ConfigCommand,
+    Debug => DebugCommand,
+    Disable => DisableCommand,
+    Enable => EnableCommand,
+    Help => HelpCommand,
+    Join => JoinCommand,
+    Leaderboard => LeaderboardCommand,
+    Odds => OddsCommand,
+    Pardon => PardonCommand,
+    Peek => PeekCommand,
+    Reload => ReloadCommand,
+    Roulette => RouletteCommand,
+    Seed => SeedCommand,
+    Spin => SpinCommand,
+    Stats => StatsCommand,
+    Status => StatusCommand,
+    Surrender => SurrenderCommand,
+    Tournament => TournamentCommand,
+}
+
+/// Trigger words of all commands, which may be remapped per deployment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Triggers(Vec<String>);
+
+impl Default for Triggers {
+    fn default() -> Self {
+        Self(
+            Commands::ALL
+                .iter()
+                .map(|command| command.name().to_string())
+                .collect(),
+        )
+    }
 }
 
-impl Commands {
-    /// Try to parse the given text to a command.
+impl Triggers {
+    /// Remap the triggers of the commands with the given canonical names, checking that the
+    /// names are known and that every trigger is valid and distinct.
+    pub fn new(remapped: &HashMap<String, String>) -> Result<Self, String> {
+        let mut triggers = Self::default();
+        for (name, trigger) in remapped {
+            let Some(command) = Commands::ALL.iter().find(|command| command.name() == name) else {
+                return Err(format!("Unknown command `{name}` in `command_triggers`"));
+            };
+            let valid = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_';
+            if trigger.is_empty() || trigger.len() > 32 || !trigger.chars().all(valid) {
+                return Err(format!(
+                    "Invalid trigger `{trigger}` for `{name}`, expected 1-32 lowercase letters, \
+                    digits or underscores"
+                ));
+            }
+            triggers.0[*command as usize] = trigger.clone();
+        }
+        for (i, trigger) in triggers.0.iter().enumerate() {
+            if let Some(other) = triggers.0[..i].iter().position(|other| other == trigger) {
+                return Err(format!(
+                    "Commands `{}` and `{}` share the trigger `{trigger}`",
+                    Commands::ALL[other].name(),
+                    Commands::ALL[i].name()
+                ));
+            }
+        }
+        Ok(triggers)
+    }
+
+    /// Get the trigger word of the command.
+    pub fn get(&self, command: Commands) -> &str {
+        &self.0[command as usize]
+    }
+
+    /// Get the command as sent, i.e. its trigger word after a slash, for mentioning in replies.
+    pub fn command(&self, command: Commands) -> String {
+        format!("/{}", self.get(command))
+    }
+
+    /// Get the command with the given trigger word, if any.
+    fn find(&self, trigger: &str) -> Option<Commands> {
+        let i = self.0.iter().position(|other| other == trigger)?;
+        Some(Commands::ALL[i])
+    }
+
+    /// Try to parse the given text to a command and its argument under these triggers.
     ///
     /// # Arguments
     ///
     /// - `text` - The text to check.
     /// - `username` - The username of the bot.
-    pub fn parse(text: Option<&String>, username: &str) -> Option<Commands> {
-        let Some(text) = text else {
-            return None;
-        };
-        let text = text.trim();
-        let (command, _arg) = text.split_once(' ').unwrap_or((text, ""));
+    pub fn parse<'a>(
+        &self,
+        text: Option<&'a String>,
+        username: &str,
+    ) -> Option<(Commands, &'a str)> {
+        let text = text?.trim();
+        let (command, arg) = text.split_once(' ').unwrap_or((text, ""));
+        let arg = arg.trim();
 
         // Two possible command formats:
         // 1. /command <arg>
@@ -59,37 +391,485 @@ impl Commands {
         }
 
         // Match the command
-        match command {
-            PeekCommand::TRIGGER => Some(Commands::Peek),
-            RouletteCommand::TRIGGER => Some(Commands::Roulette),
-            _ => None,
+        self.find(command).map(|command| (command, arg))
+    }
+
+    /// List of commands under these triggers, leaving out `/debug` unless `debug` is enabled.
+    pub fn list(&self, debug: bool) -> Vec<BotCommand> {
+        Commands::ALL
+            .iter()
+            .filter(|&&command| debug || command != Commands::Debug)
+            .map(|command| BotCommand {
+                command: self.get(*command).to_string(),
+                description: command.help().to_string(),
+            })
+            .collect()
+    }
+}
+
+/// Get the membership of the sender of the message in the chat.
+///
+/// Returns `None` if the sender or their membership cannot be determined.
+async fn get_member(bot: &impl Api, msg: &Message) -> Option<ChatMember> {
+    let Some(sender) = &msg.from else {
+        error!("Cannot determine sender of message: {msg:?}");
+        return None;
+    };
+    let get_chat_member_param = GetChatMemberParams::builder()
+        .chat_id(msg.chat.id)
+        .user_id(sender.id)
+        .build();
+    match bot.get_chat_member(&get_chat_member_param).await {
+        Ok(res) => Some(res.result),
+        Err(err) => {
+            error!(
+                "Failed to get chat member info for user ID {}: {err}",
+                sender.id
+            );
+            None
         }
     }
+}
 
-    /// Execute the command.
-    pub async fn execute(
-        &self,
-        bot: &Bot,
-        msg: Message,
-        roulette: &Mutex<Roulette>,
-    ) -> Option<String> {
-        match self {
-            Self::Peek => PeekCommand::execute(bot, msg, roulette).await,
-            Self::Roulette => RouletteCommand::execute(bot, msg, roulette).await,
+/// Determine whether the sender of the message is an admin (or the creator) of the chat.
+///
+/// Returns `None` if the sender or their role cannot be determined.
+async fn is_admin(bot: &impl Api, msg: &Message) -> Option<bool> {
+    let member = get_member(bot, msg).await?;
+    Some(is_admin_member(&member))
+}
+
+/// Mute the user in the chat until the given Unix timestamp.
+pub async fn mute_user(
+    bot: &impl Api,
+    chat_id: i64,
+    user_id: u64,
+    name: &str,
+    until: u64,
+) -> Result<(), Error> {
+    let restrict_param = RestrictChatMemberParams::builder()
+        .chat_id(chat_id)
+        .user_id(user_id)
+        .permissions(RESTRICTED_PERM)
+        .until_date(until)
+        .build();
+    bot.restrict_chat_member(&restrict_param).await?;
+    info!("Restricted user {name} until {until} in group <{chat_id}>");
+    Ok(())
+}
+
+/// Unmute the user in the chat, restoring the default permissions of the chat.
+pub async fn unmute_user(
+    bot: &impl Api,
+    chat_id: i64,
+    user_id: u64,
+    name: &str,
+) -> Result<(), Error> {
+    let get_chat_param = GetChatParams::builder().chat_id(chat_id).build();
+    let chat = bot.get_chat(&get_chat_param).await?.result;
+    // Members may not be granted more than the chat allows, e.g. sending polls where nobody can
+    let permissions = chat.permissions.unwrap_or(UNRESTRICTED_PERM);
+    let now = Timestamp::now().as_second() as u64;
+    bot.restrict_chat_member(&unrestrict_params(chat_id, user_id, permissions, now))
+        .await?;
+    info!("Unrestricted user {name} in group <{chat_id}>");
+    Ok(())
+}
+
+/// Build the parameters lifting the restriction of the user in the chat to the permissions, effective from `now`.
+fn unrestrict_params(
+    chat_id: i64,
+    user_id: u64,
+    permissions: ChatPermissions,
+    now: u64,
+) -> RestrictChatMemberParams {
+    RestrictChatMemberParams::builder()
+        .chat_id(chat_id)
+        .user_id(user_id)
+        .permissions(permissions)
+        .until_date(now)
+        .build()
+}
+
+/// Whether the member is an admin (or the creator) of the chat.
+fn is_admin_member(member: &ChatMember) -> bool {
+    matches!(
+        member,
+        ChatMember::Creator(_) | ChatMember::Administrator(_)
+    )
+}
+
+/// Whether the member is currently in the chat, i.e. has not left or been banned.
+fn is_current_member(member: &ChatMember) -> bool {
+    match member {
+        ChatMember::Left(_) | ChatMember::Kicked(_) => false,
+        ChatMember::Restricted(restricted) => restricted.is_member,
+        ChatMember::Creator(_) | ChatMember::Administrator(_) | ChatMember::Member(_) => true,
+    }
+}
+
+/// Get the message replied to, ignoring the creation of the forum topic that every message in a topic implicitly replies to.
+fn replied_message(msg: &Message) -> Option<&Message> {
+    msg.reply_to_message
+        .as_deref()
+        .filter(|replied| replied.forum_topic_created.is_none())
+}
+
+/// Get the user targeted by the command: the author of the replied message if any, otherwise the sender.
+///
+/// Returns `None` if the target is a bot, including this one, or posted anonymously on behalf of a chat, e.g. an anonymous admin, since such users cannot be restricted.
+fn resolve_target(msg: &Message) -> Option<&User> {
+    let msg = replied_message(msg).unwrap_or(msg);
+    let user = msg.from.as_deref()?;
+    if user.is_bot || msg.sender_chat.is_some() {
+        return None;
+    }
+    Some(user)
+}
+
+/// Get the name of the user to be interpolated into replies, preferring the username.
+fn display_name(user: &User) -> String {
+    let name = user.username.as_deref().unwrap_or(&user.first_name);
+    sanitize_name(name)
+}
+
+/// Sanitize a user-controlled name, by stripping control and bidirectional formatting characters and truncating it.
+fn sanitize_name(name: &str) -> String {
+    let is_bidi = |c: char| {
+        matches!(
+            c,
+            '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}'
+        )
+    };
+    let mut chars = name.chars().filter(|&c| !c.is_control() && !is_bidi(c));
+    let mut sanitized: String = chars.by_ref().take(MAX_NAME_LENGTH).collect();
+    if chars.next().is_some() {
+        sanitized.push('…');
+    }
+    if sanitized.trim().is_empty() {
+        return "Someone".to_string();
+    }
+
+    sanitized
+}
+
+/// Format the Unix timestamp in the timezone, including the date unless it's within a day from `now`.
+fn format_timestamp(timestamp: u64, now: u64, timezone: &str) -> String {
+    let timezone = TimeZone::get(timezone).unwrap_or(TimeZone::UTC);
+    let time = i64::try_from(timestamp)
+        .ok()
+        .and_then(|seconds| Timestamp::from_second(seconds).ok());
+    let Some(time) = time else {
+        return timestamp.to_string();
+    };
+    let format = if timestamp.saturating_sub(now) < 24 * 60 * 60 {
+        "%H:%M %Z"
+    } else {
+        "%Y-%m-%d %H:%M %Z"
+    };
+    time.to_zoned(timezone).strftime(format).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RouletteConfig, mock::MockApi};
+    use frankenstein::types::{ChatMemberMember, ChatMemberOwner};
+    use serde_json::json;
+
+    #[test]
+    fn test_topic_id() {
+        let message = |fields: &str| {
+            let json = format!(
+                r#"{{"message_id": 2, "date": 0, "chat": {{"id": -100, "type": "supergroup"}}{fields}}}"#
+            );
+            serde_json::from_str::<Message>(&json).unwrap()
+        };
+        assert_eq!(topic_id(&message("")), None);
+        let topic = message(r#", "message_thread_id": 5, "is_topic_message": true"#);
+        assert_eq!(topic_id(&topic), Some(5));
+        // A reply in a group without topics
+        assert_eq!(topic_id(&message(r#", "message_thread_id": 5"#)), None);
+    }
+
+    #[test]
+    fn test_resolve_target() {
+        let message = |json: &str| serde_json::from_str::<Message>(json).unwrap();
+        let sender = r#""from": {"id": 1, "is_bot": false, "first_name": "Sender"}"#;
+        let target = |fields: &str| {
+            let msg = message(&format!(
+                r#"{{"message_id": 2, "date": 0, "chat": {{"id": -100, "type": "supergroup"}}, {sender}{fields}}}"#
+            ));
+            resolve_target(&msg).map(|user| user.id)
+        };
+        let reply = |from: &str| {
+            format!(
+                r#", "reply_to_message": {{"message_id": 1, "date": 0, "chat": {{"id": -100, "type": "supergroup"}}, {from}}}"#
+            )
+        };
+
+        // The sender without a reply, the replied user otherwise
+        assert_eq!(target(""), Some(1));
+        assert_eq!(
+            target(&reply(
+                r#""from": {"id": 3, "is_bot": false, "first_name": "Ferris"}"#
+            )),
+            Some(3)
+        );
+        // Bots, including this one, are never targeted
+        assert_eq!(
+            target(&reply(
+                r#""from": {"id": 4, "is_bot": true, "first_name": "Roulette"}"#
+            )),
+            None
+        );
+        // Nor are anonymous admins
+        let anonymous = r#""from": {"id": 1087968824, "is_bot": true, "first_name": "Group"}, "sender_chat": {"id": -100, "type": "supergroup"}"#;
+        assert_eq!(target(&reply(anonymous)), None);
+        // The implicit reply to the creation of the topic is not a reply
+        let topic = format!(
+            r#"{}, "forum_topic_created": {{"name": "Games", "icon_color": 0}}"#,
+            r#""from": {"id": 3, "is_bot": false, "first_name": "Ferris"}"#
+        );
+        assert_eq!(target(&reply(&topic)), Some(1));
+    }
+
+    #[test]
+    fn test_render() {
+        let values = [("name", "Ferris"), ("duration", "60")];
+        assert_eq!(
+            render("{name} is muted for {duration}s.", &values),
+            "Ferris is muted for 60s."
+        );
+        // Unknown or unclosed placeholders are kept
+        assert_eq!(render("{name} {time} {", &values), "Ferris {time} {");
+        // Values are not rendered again
+        assert_eq!(render("{name}", &[("name", "{duration}")]), "{duration}");
+    }
+
+    #[test]
+    fn test_registry() {
+        let triggers = Triggers::default();
+        let listed: Vec<_> = triggers.list(true).into_iter().map(|c| c.command).collect();
+        assert_eq!(listed.len(), Commands::ALL.len());
+        let hidden: Vec<_> = triggers
+            .list(false)
+            .into_iter()
+            .map(|c| c.command)
+            .collect();
+        assert_eq!(hidden.len(), Commands::ALL.len() - 1);
+        assert!(!hidden.iter().any(|listed| listed == "debug"));
+        for command in Commands::ALL {
+            let trigger = triggers.get(*command);
+            assert!(
+                listed.iter().any(|listed| listed == trigger),
+                "/{trigger} is not listed"
+            );
+            let (parsed, _) = triggers.parse(Some(&format!("/{trigger}")), "bot").unwrap();
+            assert_eq!(parsed, *command);
         }
     }
 
-    /// List of commands.
-    pub fn list() -> Vec<BotCommand> {
-        vec![
-            BotCommand {
-                command: PeekCommand::TRIGGER.to_string(),
-                description: PeekCommand::HELP.to_string(),
-            },
-            BotCommand {
-                command: RouletteCommand::TRIGGER.to_string(),
-                description: RouletteCommand::HELP.to_string(),
-            },
-        ]
+    #[test]
+    fn test_parse() {
+        let triggers = Triggers::default();
+        let parse = |text: &str| {
+            triggers
+                .parse(Some(&text.to_string()), "bot")
+                .map(|(_, arg)| arg.to_string())
+        };
+        assert!(matches!(
+            triggers.parse(Some(&"/roulette".to_string()), "bot"),
+            Some((Commands::Roulette, ""))
+        ));
+        assert_eq!(
+            parse("/roulette@bot arg with spaces"),
+            Some("arg with spaces".to_string())
+        );
+        assert_eq!(parse("/peek  verbose "), Some("verbose".to_string()));
+        assert_eq!(parse("/roulette@other arg"), None);
+        assert_eq!(parse("/unknown arg"), None);
+        assert_eq!(parse("roulette"), None);
+    }
+
+    #[test]
+    fn test_remapped_triggers() {
+        let remapped = HashMap::from([("roulette".to_string(), "rr".to_string())]);
+        let triggers = Triggers::new(&remapped).unwrap();
+        assert_eq!(triggers.get(Commands::Roulette), "rr");
+        assert_eq!(triggers.get(Commands::Peek), "peek");
+        let parse = |text: &str| {
+            let text = text.to_string();
+            triggers
+                .parse(Some(&text), "bot")
+                .map(|(command, arg)| (command, arg.to_string()))
+        };
+        assert_eq!(
+            parse("/rr@bot arg"),
+            Some((Commands::Roulette, "arg".to_string()))
+        );
+        assert_eq!(parse("/roulette"), None);
+        assert!(
+            triggers
+                .list(false)
+                .iter()
+                .any(|command| command.command == "rr")
+        );
+
+        let err = |remapped: &[(&str, &str)]| {
+            let remapped = remapped
+                .iter()
+                .map(|&(k, v)| (k.to_string(), v.to_string()));
+            Triggers::new(&remapped.collect()).unwrap_err()
+        };
+        // Colliding with a remapped or a default trigger
+        assert_eq!(
+            err(&[("roulette", "shoot"), ("peek", "shoot")]),
+            "Commands `peek` and `roulette` share the trigger `shoot`"
+        );
+        assert_eq!(
+            err(&[("roulette", "peek")]),
+            "Commands `peek` and `roulette` share the trigger `peek`"
+        );
+        assert_eq!(
+            err(&[("shoot", "rr")]),
+            "Unknown command `shoot` in `command_triggers`"
+        );
+        assert!(err(&[("roulette", "Roulette")]).starts_with("Invalid trigger"));
+    }
+
+    #[test]
+    fn test_reload_if_empty() {
+        let config = RouletteConfig {
+            chambers: 1,
+            bullets: 1,
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        let reloaded = "The gun has been reloaded, with 1 bullets in 1 chambers.";
+        let mut roulette = config.clone().start().unwrap();
+        assert_eq!(
+            reload_if_empty(&mut roulette, &Triggers::default()),
+            (String::new(), None)
+        );
+        roulette.fire();
+        assert_eq!(
+            reload_if_empty(&mut roulette, &Triggers::default()),
+            (format!(" {reloaded}"), None)
+        );
+        assert!(!roulette.is_empty());
+
+        let announcing = RouletteConfig {
+            announce_reload: true,
+            ..config
+        };
+        let mut roulette = announcing.start().unwrap();
+        roulette.fire();
+        assert_eq!(
+            reload_if_empty(&mut roulette, &Triggers::default()),
+            (String::new(), Some(reloaded.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_unrestrict_params() {
+        let params = unrestrict_params(-100, 1, UNRESTRICTED_PERM, 60);
+        assert_eq!(params.until_date, Some(60));
+        // Every permission taken away is given back, and nothing else
+        let granted = |perm: ChatPermissions| {
+            [
+                perm.can_send_messages,
+                perm.can_send_audios,
+                perm.can_send_documents,
+                perm.can_send_photos,
+                perm.can_send_videos,
+                perm.can_send_video_notes,
+                perm.can_send_voice_notes,
+                perm.can_send_polls,
+                perm.can_send_other_messages,
+                perm.can_add_web_page_previews,
+                perm.can_change_info,
+                perm.can_invite_users,
+                perm.can_pin_messages,
+                perm.can_manage_topics,
+            ]
+        };
+        let restricted = granted(RESTRICTED_PERM);
+        let unrestricted = granted(params.permissions);
+        for (restricted, unrestricted) in restricted.into_iter().zip(unrestricted) {
+            assert_eq!(restricted.map(|granted| !granted), unrestricted);
+        }
+        assert_eq!(unrestricted[..9], [Some(true); 9]);
+    }
+
+    #[tokio::test]
+    async fn test_unmute_user() {
+        // Polls are disabled for everyone in this chat
+        let chat = json!({
+            "id": -100,
+            "type": "supergroup",
+            "permissions": {"can_send_messages": true, "can_send_polls": false}
+        });
+        let api = MockApi::default()
+            .with("getChat", chat)
+            .with("restrictChatMember", json!(true));
+        unmute_user(&api, -100, 1, "Ferris").await.unwrap();
+
+        let restrictions = api.requests("restrictChatMember");
+        let permissions = &restrictions[0]["permissions"];
+        assert_eq!(permissions["can_send_messages"], true);
+        assert_eq!(permissions["can_send_polls"], false);
+        assert!(permissions.get("can_send_photos").is_none());
+    }
+
+    #[test]
+    fn test_is_admin_member() {
+        let user = User::builder()
+            .id(1)
+            .is_bot(false)
+            .first_name("Ferris")
+            .build();
+        let creator = ChatMember::Creator(
+            ChatMemberOwner::builder()
+                .user(user.clone())
+                .is_anonymous(false)
+                .build(),
+        );
+        assert!(is_admin_member(&creator));
+        let member = ChatMember::Member(ChatMemberMember::builder().user(user).build());
+        assert!(!is_admin_member(&member));
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        // 2024-01-01 23:59:00 UTC
+        let now = 1_704_153_540;
+        assert_eq!(format_timestamp(now, now, "UTC"), "23:59 UTC");
+        // Rolls over midnight
+        assert_eq!(format_timestamp(now + 120, now, "UTC"), "00:01 UTC");
+        assert_eq!(format_timestamp(now, now, "Asia/Tokyo"), "08:59 JST");
+        assert_eq!(
+            format_timestamp(now - 9 * 3600 + 120, now, "Asia/Tokyo"),
+            "00:01 JST"
+        );
+        // Includes the date if more than a day away
+        assert_eq!(
+            format_timestamp(now + 2 * 24 * 3600, now, "UTC"),
+            "2024-01-03 23:59 UTC"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_name() {
+        // Emoji are kept
+        assert_eq!(sanitize_name("🦀 Ferris"), "🦀 Ferris");
+        // Control and bidirectional override characters are stripped
+        assert_eq!(sanitize_name("evil\u{202E}gnp.exe\n"), "evilgnp.exe");
+        assert_eq!(sanitize_name("\u{0007}\u{202E}"), "Someone");
+        // Long names are truncated
+        let long = "a".repeat(200);
+        let sanitized = sanitize_name(&long);
+        assert_eq!(sanitized.chars().count(), MAX_NAME_LENGTH + 1);
+        assert!(sanitized.ends_with('…'));
     }
 }
@@ -0,0 +1,76 @@
+use super::{
+    Api, Command, CommandOutcome, Commands, Context, Group, Triggers, gun_name, no_such_gun,
+    paused, render, reply, topic_id,
+};
+use crate::{PeekMode, Roulette, locale::MessageKey};
+use frankenstein::types::Message;
+use tokio::sync::Mutex;
+
+/// Tell the chance of getting shot on the next pull.
+pub struct OddsCommand;
+
+impl Command for OddsCommand {
+    const TRIGGER: &'static str = "odds";
+    const HELP: &'static str = "Tell the chance of getting shot on the next pull.";
+    const INFORMATIONAL: bool = true;
+    async fn execute(
+        context: &Context<impl Api>,
+        msg: Message,
+        arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let triggers = &context.triggers();
+        let gun = gun_name(arg);
+        let mut group = group.lock().await;
+        if !group.has_gun(gun) {
+            return reply(no_such_gun(&group, gun));
+        }
+        let Some(roulette) = group.playable_gun(topic_id(&msg), gun) else {
+            return reply(paused(&group));
+        };
+        reply(odds_reply(roulette, triggers))
+    }
+}
+
+/// Compose the reply telling the odds, unless the peek mode hides the counts they are derived from.
+fn odds_reply(roulette: &Roulette, triggers: &Triggers) -> String {
+    let config = roulette.config();
+    if config.peek_mode() == PeekMode::Next {
+        return render(
+            config.message(MessageKey::OddsSecret),
+            &[("peek", &triggers.command(Commands::Peek))],
+        );
+    }
+    let percent = format!("{:.1}", roulette.odds() * 100.0);
+    render(config.message(MessageKey::Odds), &[("percent", &percent)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RouletteConfig;
+
+    #[test]
+    fn test_odds_reply() {
+        let config = RouletteConfig {
+            chambers: 6,
+            bullets: 2,
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        let mut roulette = config.start().unwrap();
+        assert_eq!(
+            odds_reply(&roulette, &Triggers::default()),
+            "The chance of getting shot on the next pull is 33.3%."
+        );
+
+        let secret = RouletteConfig {
+            peek_mode: PeekMode::Next,
+            ..Default::default()
+        };
+        roulette.reconfigure(secret).unwrap();
+        assert!(
+            odds_reply(&roulette, &Triggers::default()).starts_with("The odds are a secret here")
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use super::{
+    Api, Command, CommandOutcome, Context, Group, display_name, get_member, is_admin_member,
+    render, replied_message, reply, resolve_target,
+};
+use crate::{RouletteConfig, locale::MessageKey};
+use frankenstein::types::{ChatMember, Message};
+use tokio::sync::Mutex;
+
+/// Unmute a user early, given by replying to their message or by their user ID. Admin only.
+pub struct PardonCommand;
+
+impl Command for PardonCommand {
+    const TRIGGER: &'static str = "pardon";
+    const HELP: &'static str =
+        "Unmute a user early, by replying to their message or with their user ID. Admin only.";
+    async fn execute(
+        context: &Context<impl Api>,
+        msg: Message,
+        arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let bot = context.bot.as_ref();
+        let Some(member) = get_member(bot, &msg).await else {
+            return Vec::new();
+        };
+        let group = group.lock().await;
+        let config = group.roulette().config();
+        match pardon_target(config, &member, &msg, arg) {
+            Ok((user_id, name)) => {
+                let text = render(config.message(MessageKey::Pardoned), &[("name", &name)]);
+                vec![
+                    CommandOutcome::Unrestrict { user_id, name },
+                    CommandOutcome::Reply(text),
+                ]
+            }
+            Err(reason) => reply(config.message(reason).to_string()),
+        }
+    }
+}
+
+/// Get the user to pardon as their ID and name, if the sender is an admin and has given one.
+///
+/// The author of the replied message takes precedence over the user ID in the argument, which
+/// names the user as in the [`MessageKey::User`] text of the config.
+fn pardon_target(
+    config: &RouletteConfig,
+    sender: &ChatMember,
+    msg: &Message,
+    arg: &str,
+) -> Result<(u64, String), MessageKey> {
+    if !is_admin_member(sender) {
+        return Err(MessageKey::PardonAdminOnly);
+    }
+    if replied_message(msg).is_some() {
+        return resolve_target(msg)
+            .map(|user| (user.id, display_name(user)))
+            .ok_or(MessageKey::PardonBot);
+    }
+    match arg.parse::<u64>() {
+        Ok(user_id) => {
+            let name = render(
+                config.message(MessageKey::User),
+                &[("id", &user_id.to_string())],
+            );
+            Ok((user_id, name))
+        }
+        Err(_) => Err(MessageKey::PardonUsage),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frankenstein::types::{ChatMemberMember, ChatMemberOwner, User};
+
+    #[test]
+    fn test_pardon_target() {
+        let user = User::builder()
+            .id(1)
+            .is_bot(false)
+            .first_name("Admin")
+            .build();
+        let owner = ChatMember::Creator(
+            ChatMemberOwner::builder()
+                .user(user.clone())
+                .is_anonymous(false)
+                .build(),
+        );
+        let member = ChatMember::Member(ChatMemberMember::builder().user(user).build());
+        let message = |reply_to: &str| -> Message {
+            serde_json::from_str(&format!(
+                r#"{{"message_id": 2, "date": 0, "chat": {{"id": -100, "type": "supergroup"}}{reply_to}}}"#
+            ))
+            .unwrap()
+        };
+        let plain = message("");
+        let replying = message(
+            r#", "reply_to_message": {"message_id": 1, "date": 0, "chat": {"id": -100, "type": "supergroup"}, "from": {"id": 3, "is_bot": false, "first_name": "Ferris"}}"#,
+        );
+
+        // Admin only
+        let config = RouletteConfig::default();
+        let pardon_target = |sender, msg, arg| pardon_target(&config, sender, msg, arg);
+        let not_admin = Err(MessageKey::PardonAdminOnly);
+        assert_eq!(pardon_target(&member, &replying, ""), not_admin);
+        assert_eq!(pardon_target(&member, &plain, "3"), not_admin);
+
+        // The replied user takes precedence
+        let ferris = Ok((3, "Ferris".to_string()));
+        assert_eq!(pardon_target(&owner, &replying, ""), ferris);
+        assert_eq!(pardon_target(&owner, &replying, "4"), ferris);
+        assert_eq!(
+            pardon_target(&owner, &plain, "4"),
+            Ok((4, "User 4".to_string()))
+        );
+        let usage = Err(MessageKey::PardonUsage);
+        assert_eq!(pardon_target(&owner, &plain, ""), usage);
+        assert_eq!(pardon_target(&owner, &plain, "Ferris"), usage);
+    }
+}
@@ -1,5 +1,9 @@
-use super::{Command, Roulette};
-use frankenstein::{client_reqwest::Bot, types::Message};
+use super::{
+    Api, Command, CommandOutcome, Context, Group, gun_name, no_such_gun, paused, reload_if_empty,
+    render, reply, topic_id,
+};
+use crate::{FireMode, PeekMode, Roulette, locale::MessageKey};
+use frankenstein::types::Message;
 use tokio::sync::Mutex;
 
 /// Peek the left-over chambers, acquiring count of filled and left chambers.
@@ -9,14 +13,237 @@ impl Command for PeekCommand {
     const TRIGGER: &'static str = "peek";
     const HELP: &'static str =
         "Peek the left-over chambers, acquiring count of filled and left chambers.";
-    async fn execute(_bot: &Bot, _msg: Message, roulette: &Mutex<Roulette>) -> Option<String> {
+    const INFORMATIONAL: bool = true;
+    async fn execute(
+        context: &Context<impl Api>,
+        msg: Message,
+        arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let triggers = &context.triggers();
         // Peek the roulette
-        let roulette = roulette.lock().await;
-        let (filled, left) = roulette.peek();
+        let gun = gun_name(arg);
+        let mut group = group.lock().await;
+        if !group.has_gun(gun) {
+            return reply(no_such_gun(&group, gun));
+        }
+        let Some(roulette) = group.playable_gun(topic_id(&msg), gun) else {
+            return reply(paused(&group));
+        };
+        if roulette.discharge_on_peek() {
+            let (reload_tip, announcement) = reload_if_empty(roulette, triggers);
+            let text = roulette.config().message(MessageKey::PeekFired).to_string() + &reload_tip;
+            let mut outcomes = vec![CommandOutcome::LastingReply(text)];
+            outcomes.extend(announcement.map(CommandOutcome::Send));
+            return outcomes;
+        }
         // Respond with the result
-        let response = format!(
-            "You stole a quick glimpse at the revolver... There're {filled} filled chambers, out of {left} left-over chambers."
+        reply(peek_reply(roulette) + &jam_notice(roulette))
+    }
+}
+
+/// Compose the notice of the current jam probability to append with `peek_shows_jam`, empty if
+/// disabled or the gun cannot jam.
+fn jam_notice(roulette: &Roulette) -> String {
+    let probability = roulette.jam_probability();
+    if !roulette.config().peek_shows_jam() || probability == 0.0 {
+        return String::new();
+    }
+    // Rounded to a tenth of a percent, which displays without trailing zeros
+    let chance = (probability * 1000.0).round() / 10.0;
+    let text = render(
+        roulette.config().message(MessageKey::PeekJam),
+        &[("chance", &chance.to_string())],
+    );
+    format!(" {text}")
+}
+
+/// Compose the reply revealing what the configured peek mode allows.
+fn peek_reply(roulette: &Roulette) -> String {
+    let config = roulette.config();
+    // Pulls are decided independently in the probabilistic mode, so there's no next chamber to reveal
+    if config.peek_mode() == PeekMode::Next && config.mode() == FireMode::Fixed {
+        let key = if roulette.peek_next() {
+            MessageKey::PeekLoaded
+        } else {
+            MessageKey::PeekEmpty
+        };
+        return config.message(key).to_string();
+    }
+    let (filled, left) = roulette.peek();
+    render(
+        config.message(MessageKey::Peek),
+        &[("filled", &filled.to_string()), ("left", &left.to_string())],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        RouletteConfig,
+        mock::{MockApi, context, message},
+    };
+
+    #[test]
+    fn test_peek_reply() {
+        let config = RouletteConfig {
+            chambers: 3,
+            bullets: 1,
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        // Find a loading with the bullet in the second chamber
+        let mut roulette = (0..)
+            .map(|seed| Roulette::from_seed(config.clone(), seed).unwrap())
+            .find(|roulette| roulette.snapshot().contents == [false, true, false])
+            .unwrap();
+        let mut next = roulette.clone();
+        next.reconfigure(RouletteConfig {
+            peek_mode: PeekMode::Next,
+            ..config
+        })
+        .unwrap();
+
+        let glimpse = "You stole a quick glimpse at the revolver...";
+        assert_eq!(
+            peek_reply(&roulette),
+            format!("{glimpse} There're 1 filled chambers, out of 3 left-over chambers.")
+        );
+        assert_eq!(
+            peek_reply(&next),
+            format!("{glimpse} The next chamber is empty.")
+        );
+
+        roulette.fire();
+        next.fire();
+        assert_eq!(
+            peek_reply(&roulette),
+            format!("{glimpse} There're 1 filled chambers, out of 2 left-over chambers.")
+        );
+        assert_eq!(
+            peek_reply(&next),
+            format!("{glimpse} The next chamber is loaded.")
+        );
+
+        // No bullets left
+        roulette.fire();
+        next.fire();
+        assert_eq!(
+            peek_reply(&roulette),
+            format!("{glimpse} There're 0 filled chambers, out of 1 left-over chambers.")
+        );
+        assert_eq!(
+            peek_reply(&next),
+            format!("{glimpse} The next chamber is empty.")
+        );
+        // No chambers left
+        next.fire();
+        assert!(!next.peek_next());
+        assert_eq!(
+            peek_reply(&next),
+            format!("{glimpse} The next chamber is empty.")
+        );
+    }
+
+    #[test]
+    fn test_jam_notice() {
+        let config = RouletteConfig {
+            jam_probability: 0.05,
+            jam_escalation: 0.025,
+            ..Default::default()
+        };
+        // Disabled by default
+        let mut roulette = Roulette::from_seed(config.clone(), 0).unwrap();
+        assert_eq!(jam_notice(&roulette), "");
+
+        roulette
+            .reconfigure(RouletteConfig {
+                peek_shows_jam: true,
+                ..config.clone()
+            })
+            .unwrap();
+        assert_eq!(
+            jam_notice(&roulette),
+            " The mechanism looks shaky (5% jam chance)."
+        );
+        // Escalates with the chambers fired
+        roulette.fire_many(1);
+        assert_eq!(
+            jam_notice(&roulette),
+            " The mechanism looks shaky (7.5% jam chance)."
+        );
+
+        // Nothing to tell if the gun cannot jam
+        let steady = RouletteConfig {
+            jam_probability: 0.0,
+            jam_escalation: 0.0,
+            peek_shows_jam: true,
+            ..config
+        };
+        assert_eq!(jam_notice(&Roulette::from_seed(steady, 0).unwrap()), "");
+    }
+
+    #[tokio::test]
+    async fn test_peek() {
+        let msg = message("/peek");
+        let config = RouletteConfig {
+            chambers: 6,
+            bullets: 2,
+            ..Default::default()
+        };
+        let group = Mutex::new(Group::new(config.start().unwrap()));
+        // Peeking only reads the state, thus never contacts Telegram
+        let outcomes = PeekCommand::execute(&context(MockApi::default()), msg, "", &group).await;
+        assert_eq!(
+            outcomes,
+            [CommandOutcome::Reply(
+                "You stole a quick glimpse at the revolver... There're 2 filled chambers, out of 6 left-over chambers.".to_string()
+            )]
+        );
+        assert_eq!(group.lock().await.roulette().position(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_peek_discharges() {
+        let msg = message("/peek");
+        let config = RouletteConfig {
+            chambers: 6,
+            bullets: 6,
+            peek_fire_probability: 1.0,
+            ..Default::default()
+        };
+        let group = Mutex::new(Group::new(config.start().unwrap()));
+        let outcomes =
+            PeekCommand::execute(&context(MockApi::default()), msg.clone(), "", &group).await;
+        let [CommandOutcome::LastingReply(text)] = &outcomes[..] else {
+            panic!("Expected a single lasting reply: {outcomes:?}");
+        };
+        assert!(text.starts_with("Bang! The gun went off while you were peeking"));
+        // The chamber is wasted, without muting anyone
+        let stats = {
+            let group = group.lock().await;
+            assert_eq!(group.roulette().peek(), (5, 5));
+            group.roulette().stats()
+        };
+        assert_eq!((stats.fires, stats.bullets), (1, 0));
+
+        // Reloads once the last chamber is wasted
+        let config = RouletteConfig {
+            chambers: 1,
+            bullets: 1,
+            peek_fire_probability: 1.0,
+            ..Default::default()
+        };
+        let group = Mutex::new(Group::new(config.start().unwrap()));
+        let outcomes = PeekCommand::execute(&context(MockApi::default()), msg, "", &group).await;
+        let [CommandOutcome::LastingReply(text)] = &outcomes[..] else {
+            panic!("Expected a single lasting reply: {outcomes:?}");
+        };
+        assert!(
+            text.ends_with(" The gun has been reloaded, with 1 bullets in 1 chambers."),
+            "{text}"
         );
-        Some(response)
+        assert!(!group.lock().await.roulette().is_empty());
     }
 }
@@ -0,0 +1,77 @@
+use super::{
+    Api, Command, CommandOutcome, Context, Group, gun_name, is_admin, message, no_such_gun,
+    reloaded_reply, reply, topic_id,
+};
+use crate::locale::MessageKey;
+use frankenstein::types::Message;
+use tokio::sync::Mutex;
+
+/// Reload the revolver with a fresh loading, clearing any jam. Takes an optional gun name, e.g. `/reload left`.
+pub struct ReloadCommand;
+
+impl Command for ReloadCommand {
+    const TRIGGER: &'static str = "reload";
+    const HELP: &'static str = "Reload the revolver with a fresh loading. Admin only.";
+    async fn execute(
+        context: &Context<impl Api>,
+        msg: Message,
+        arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let bot = context.bot.as_ref();
+        let Some(admin) = is_admin(bot, &msg).await else {
+            return Vec::new();
+        };
+        if !admin {
+            return reply(message(&*group.lock().await, MessageKey::ReloadAdminOnly));
+        }
+        let gun = gun_name(arg);
+        let mut group = group.lock().await;
+        let topic = topic_id(&msg);
+        group.clear_jam(topic, gun);
+        let Some(roulette) = group.gun_mut(topic, gun) else {
+            return reply(no_such_gun(&group, gun));
+        };
+        roulette.reload();
+        reply(reloaded_reply(roulette))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        RouletteConfig,
+        mock::{MockApi, context, message},
+    };
+
+    #[tokio::test]
+    async fn test_reload() {
+        let config = RouletteConfig {
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        let group = Mutex::new(Group::new(config.start().unwrap()));
+        group.lock().await.roulette_mut().fire();
+
+        // Refused for members, leaving the revolver as is
+        let api = context(MockApi::default().with_member("member"));
+        let outcomes = ReloadCommand::execute(&api, message("/reload"), "", &group).await;
+        assert_eq!(
+            outcomes,
+            reply("Sorry, only admins can reload the revolver".to_string())
+        );
+        assert_eq!(group.lock().await.roulette().position(), 1);
+
+        // Allowed for admins
+        let api = context(MockApi::default().with_member("creator"));
+        let outcomes = ReloadCommand::execute(&api, message("/reload"), "", &group).await;
+        assert_eq!(
+            outcomes,
+            reply("The gun has been reloaded, with 2 bullets in 6 chambers.".to_string())
+        );
+        let group = group.lock().await;
+        assert_eq!(group.roulette().position(), 0);
+        assert_eq!(group.roulette().stats().reloads, 1);
+    }
+}
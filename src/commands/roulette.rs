@@ -1,100 +1,871 @@
-use super::{Command, Roulette};
-use crate::constants::RESTRICTED_PERM;
-use frankenstein::{
-    AsyncTelegramApi,
-    client_reqwest::Bot,
-    methods::{GetChatMemberParams, RestrictChatMemberParams},
-    types::{ChatMember, Message},
+use super::{
+    Api, Command, CommandOutcome, Commands, Context, Group, Triggers, display_name,
+    format_timestamp, get_member, gun_name, is_admin_member, is_current_member, message,
+    no_such_gun, paused, reload_if_empty, reloaded_reply, render, reply, topic_id,
 };
-use log::{error, info};
-use tokio::sync::Mutex;
-use crate::FireResult;
+use crate::{FireResult, Roulette, RouletteConfig, locale::MessageKey};
+use frankenstein::types::{ChatMember, Message};
+use jiff::Timestamp;
+use log::error;
+use std::time::Duration;
+use tokio::{sync::Mutex, time::sleep};
 
-/// Joins the roulette game.
+/// Joins the roulette game. Takes an optional gun name and number of shots, e.g. `/roulette 3`.
 pub struct RouletteCommand;
 
 impl Command for RouletteCommand {
     const TRIGGER: &'static str = "roulette";
     const HELP: &'static str = "Joins the roulette game.";
-    async fn execute(bot: &Bot, msg: Message, roulette: &Mutex<Roulette>) -> Option<String> {
-        // Get chat and sender
-        let chat = &msg.chat;
+    async fn execute(
+        context: &Context<impl Api>,
+        msg: Message,
+        arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let bot = context.bot.as_ref();
+        let triggers = &context.triggers();
+        // Get sender
         let Some(sender) = &msg.from else {
             error!("Cannot determine sender of message: {msg:?}");
-            return None;
+            return Vec::new();
         };
         // Determine sender's role
-        let get_chat_member_param = GetChatMemberParams::builder()
-            .chat_id(chat.id)
-            .user_id(sender.id)
-            .build();
-        let member = match bot.get_chat_member(&get_chat_member_param).await {
-            Ok(res) => res.result,
-            Err(err) => {
-                error!(
-                    "Failed to get chat member info for user ID {}: {err}",
-                    sender.id
-                );
-                return None;
+        let Some(member) = get_member(bot, &msg).await else {
+            return Vec::new();
+        };
+        {
+            let group = group.lock().await;
+            let admins_can_play = group.roulette().config().admins_can_play();
+            if let Some(reason) = rejection(&member, admins_can_play) {
+                return reply(message(&group, reason));
+            }
+        }
+        let name = display_name(sender);
+        let (gun, shots) = parse_arg(arg);
+        let topic = topic_id(&msg);
+
+        // Give the player a chance to back out
+        let (window, prompt) = {
+            let mut group = group.lock().await;
+            if !group.is_enabled() {
+                return reply(paused(&group));
             }
+            let Some(roulette) = group.gun(topic, gun) else {
+                return reply(no_such_gun(&group, gun));
+            };
+            let config = roulette.config().clone();
+            let window = config.chicken_window_secs();
+            if let Some(left) = group.jam_cooldown(topic, gun) {
+                return reply(render(
+                    config.message(MessageKey::JamCooldown),
+                    &[("seconds", &left.as_secs_f64().ceil().to_string())],
+                ));
+            }
+            let now = Timestamp::now().as_second() as u64;
+            if let Some(left) = group.play_cooldown(sender.id, gun, now) {
+                return reply(render(
+                    config.message(MessageKey::PlayCooldown),
+                    &[("name", &name), ("seconds", &left.to_string())],
+                ));
+            }
+            if window > 0 && !group.begin_pull(sender.id) {
+                return reply(render(
+                    config.message(MessageKey::Holding),
+                    &[("name", &name)],
+                ));
+            }
+            let prompt = render(
+                config.message(MessageKey::ChickenPrompt),
+                &[
+                    ("name", &name),
+                    ("chicken", &triggers.command(Commands::Chicken)),
+                    ("seconds", &window.to_string()),
+                ],
+            );
+            (window, prompt)
         };
-        let is_admin = matches!(
-            member,
-            ChatMember::Creator(_) | ChatMember::Administrator(_)
-        );
-        if is_admin {
-            return Some("Cannot play roulette as an admin".to_string());
+        if window > 0 {
+            context.announce(&msg, prompt).await;
+            sleep(Duration::from_secs(window)).await;
+            if !group.lock().await.finish_pull(sender.id) {
+                // Backed out with `/chicken`, which has replied already
+                return Vec::new();
+            }
         }
-        // Check the roulette status
-        let mut roulette = roulette.lock().await;
-        let result = roulette.fire();
 
-        // Reload the gun if empty
-        let reload_tip = if roulette.peek().0 == 0 {
-            roulette.reload();
-            let (bullets, chambers) = roulette.info();
-            format!(" The gun has been reloaded, with {bullets} bullets in {chambers} chambers.")
+        // Check the roulette status
+        let mut group = group.lock().await;
+        let fires = group.stats().fires;
+        let warning_shot = group.in_grace_period(Timestamp::now().as_second() as u64);
+        let Some(roulette) = group.playable_gun(topic, gun) else {
+            return reply(paused(&group));
+        };
+        // Decided before firing, so that a warning shot is never recorded as a bullet
+        let fire = if warning_shot {
+            Roulette::fire_warning_shots
         } else {
-            String::new()
-        };
-
-        // Apply action and return the message
-        let name = sender.username.as_deref();
-        let name = name.unwrap_or(&sender.first_name);
-        match result {
-            FireResult::Bullet => {
-                // Restrict the user for a certain period
-                let (duration, until) = roulette.random_mute_until();
-                let restrict_param = RestrictChatMemberParams::builder()
-                    .chat_id(chat.id)
-                    .user_id(sender.id)
-                    .permissions(RESTRICTED_PERM)
-                    .until_date(until)
-                    .build();
-                match bot.restrict_chat_member(&restrict_param).await {
-                    Ok(_) => {
-                        info!(
-                            "Restricted user {name} for {duration}s in group <{}>",
-                            chat.id
-                        );
-                    }
-                    Err(err) => {
-                        error!("Failed to restrict user {name}: {err}");
-                        return None;
+            Roulette::fire_many
+        };
+        let mut results = fire(roulette, shots);
+        if results == [FireResult::NoBullets] {
+            if !roulette.config().auto_reload() {
+                return reply(render(
+                    roulette.config().message(MessageKey::Empty),
+                    &[("reload", &triggers.command(Commands::Reload))],
+                ));
+            }
+            // Reload and retry, so that the turn isn't wasted
+            roulette.reload();
+            results = fire(roulette, shots);
+        }
+        let result = *results.last().expect("At least one shot is fired");
+        let config = roulette.config().clone();
+
+        if result != FireResult::NoBullets {
+            roulette.record_player(sender.id, &name, result.is_bullet());
+        }
+        let winners = settle_survivors(roulette, triggers);
+        let (reload_tip, announcement) = reload_if_empty(roulette, triggers);
+
+        group.record_play(sender.id, gun, Timestamp::now().as_second() as u64);
+        let milestone =
+            crossed_milestone(config.milestones(), fires, group.stats().fires).map(|fires| {
+                render(
+                    config.message(MessageKey::Milestone),
+                    &[("fires", &fires.to_string())],
+                )
+            });
+
+        // Record the results and return the actions
+        let mut jackpot = None;
+        for &result in &results {
+            match result {
+                FireResult::Bullet { .. } => {
+                    group.record_shot(sender.id, gun);
+                    group.record_outcome(sender.id, &name, true);
+                }
+                FireResult::Empty | FireResult::Misfire => {
+                    group.record_outcome(sender.id, &name, false);
+                    if group.record_survival(sender.id, gun) {
+                        jackpot = Some(render(
+                            config.message(MessageKey::Jackpot),
+                            &[
+                                ("name", &name),
+                                ("streak", &config.jackpot_streak().to_string()),
+                            ],
+                        ));
                     }
-                };
-                Some(format!("Bang! {name} was shot and muted for {duration}s.",) + &reload_tip)
-            },
-            FireResult::Empty => {
-                Some(format!("Click! {name} is safe and sound.",) + &reload_tip)
-            },
-            FireResult::Jammed => {
-                Some(format!("Click? You're lucky that the gun got jammed.",) + &reload_tip)
-            },
-            FireResult::NoBullets => {
-                // This should not happen, but just in case
-                Some(format!("Click? The gun is somehow empty...",) + &reload_tip)
-            },
+                }
+                FireResult::Jammed => group.jam(topic, gun),
+                FireResult::WarningShot | FireResult::NoBullets => {}
+            }
+        }
+        let shooter = Shooter {
+            user_id: sender.id,
+            name: &name,
+            is_admin: is_admin_member(&member),
+        };
+        // React to the command message first, as the gun that fired is configured to
+        let reaction = config
+            .reactions()
+            .and_then(|reactions| reactions.get(result));
+        let mut outcomes: Vec<_> = reaction
+            .map(|emoji| CommandOutcome::React(emoji.to_string()))
+            .into_iter()
+            .collect();
+        outcomes.extend(pull_outcomes(
+            &config,
+            &results,
+            &shooter,
+            reload_tip,
+            announcement,
+        ));
+        outcomes.extend(jackpot.map(CommandOutcome::SendPinned));
+        outcomes.extend(winners.map(CommandOutcome::Send));
+        outcomes.extend(milestone.map(CommandOutcome::Send));
+        outcomes
+    }
+}
+
+/// Get the highest milestone crossed by the total pulls going from `before` to `after`, if any.
+fn crossed_milestone(milestones: &[u64], before: u64, after: u64) -> Option<u64> {
+    milestones
+        .iter()
+        .copied()
+        .filter(|&milestone| before < milestone && milestone <= after)
+        .max()
+}
+
+/// Split the argument into the gun name and the number of shots, which is the last word if
+/// it is a number, defaulting to one.
+fn parse_arg(arg: &str) -> (&str, usize) {
+    let (gun, shots) = arg.rsplit_once(' ').unwrap_or(("", arg));
+    match shots.parse() {
+        Ok(shots) => (gun_name(gun.trim_end()), shots),
+        Err(_) => (gun_name(arg), 1),
+    }
+}
+
+/// The player pulling the trigger.
+struct Shooter<'a> {
+    /// ID of the user.
+    user_id: u64,
+    /// Display name of the user.
+    name: &'a str,
+    /// Whether the user is an admin, who cannot be muted.
+    is_admin: bool,
+}
+
+/// Compose the actions for the results of the pulls: muting the shooter if shot, replying with
+/// the narrated outcome and the reload tip, then the reload announcement if any.
+fn pull_outcomes(
+    config: &RouletteConfig,
+    results: &[FireResult],
+    shooter: &Shooter,
+    reload_tip: String,
+    announcement: Option<String>,
+) -> Vec<CommandOutcome> {
+    let mut outcomes = Vec::new();
+    let (&result, clicks) = results.split_last().expect("At least one shot is fired");
+    let reply = outcome_reply(config, result, shooter.name);
+    let click = format!("{} ", config.message(MessageKey::Click));
+    let mut text = click.repeat(clicks.len()) + &reply;
+    if let FireResult::Bullet { until, .. } = result {
+        if shooter.is_admin {
+            // Telegram refuses to restrict admins
+            text += " ";
+            text += config.message(MessageKey::AdminNotMuted);
+        } else {
+            outcomes.push(CommandOutcome::Restrict {
+                user_id: shooter.user_id,
+                name: shooter.name.to_string(),
+                until,
+            });
+        }
+    }
+    outcomes.push(CommandOutcome::Reply(text + &reload_tip));
+    outcomes.extend(announcement.map(CommandOutcome::Send));
+    outcomes
+}
+
+/// Announce the survivors as winners and reload if only bullets are left with `survivors_win`,
+/// returning the announcement.
+///
+/// With `auto_reload` disabled, the survivors are announced only once, leaving the reload to an
+/// admin.
+fn settle_survivors(roulette: &mut Roulette, triggers: &Triggers) -> Option<String> {
+    if !roulette.config().survivors_win()
+        || !roulette.only_bullets_left()
+        || roulette.survivors().is_empty()
+    {
+        return None;
+    }
+    let names: Vec<_> = roulette
+        .take_survivors()
+        .into_iter()
+        .map(|(_, name)| name)
+        .collect();
+    let text = render(
+        roulette.config().message(MessageKey::Winners),
+        &[("names", &names.join(", "))],
+    );
+    if !roulette.config().auto_reload() {
+        let another_round = render(
+            roulette.config().message(MessageKey::AnotherRound),
+            &[("reload", &triggers.command(Commands::Reload))],
+        );
+        return Some(format!("{text} {another_round}"));
+    }
+    // The loading is over, as the next pull would certainly be a bullet
+    roulette.reload();
+    Some(format!("{text} {}", reloaded_reply(roulette)))
+}
+
+/// Get the reason why the member cannot play, or `None` if they can.
+fn rejection(member: &ChatMember, admins_can_play: bool) -> Option<MessageKey> {
+    match member {
+        ChatMember::Creator(_) | ChatMember::Administrator(_) if !admins_can_play => {
+            Some(MessageKey::AdminCannotPlay)
+        }
+        // The owner can never be restricted
+        ChatMember::Creator(_) => Some(MessageKey::OwnerCannotPlay),
+        _ if !is_current_member(member) => Some(MessageKey::NotMember),
+        _ => None,
+    }
+}
+
+/// Compose the reply for the outcome of a pull.
+fn outcome_reply(config: &RouletteConfig, result: FireResult, name: &str) -> String {
+    match result {
+        FireResult::Bullet { duration, until } => {
+            let time = format_timestamp(until, until - duration, config.timezone());
+            render(
+                config.message(MessageKey::Shot),
+                &[
+                    ("name", name),
+                    ("duration", &duration.to_string()),
+                    ("time", &time),
+                ],
+            )
+        }
+        FireResult::Empty => render(config.message(MessageKey::Safe), &[("name", name)]),
+        FireResult::Jammed => render(config.message(MessageKey::Jammed), &[("name", name)]),
+        FireResult::Misfire => render(config.message(MessageKey::Misfire), &[("name", name)]),
+        FireResult::WarningShot => {
+            render(config.message(MessageKey::WarningShot), &[("name", name)])
+        }
+        // This should not happen even after retrying, but just in case
+        FireResult::NoBullets => config.message(MessageKey::SomehowEmpty).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        GroupConfig,
+        group::DEFAULT_GUN,
+        locale::Locale,
+        mock::{MockApi, context, message},
+    };
+
+    #[test]
+    fn test_outcome_reply() {
+        let config = RouletteConfig {
+            chambers: 2,
+            bullets: 1,
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        // Find a loading with the bullet in the last chamber
+        let mut roulette = (0..)
+            .map(|seed| Roulette::from_seed(config.clone(), seed).unwrap())
+            .find(|roulette| roulette.peek() == (1, 2) && !roulette.contents[0])
+            .unwrap();
+
+        let result = roulette.fire();
+        assert_eq!(result, FireResult::Empty);
+        assert_eq!(
+            outcome_reply(&config, result, "Ferris"),
+            "Click! Ferris is safe and sound."
+        );
+        assert!(roulette.fire().is_bullet());
+        let result = FireResult::Bullet {
+            duration: 60,
+            until: 60,
+        };
+        assert_eq!(
+            outcome_reply(&config, result, "Ferris"),
+            "Bang! Ferris was shot and muted for 60s, until 00:01 UTC."
+        );
+        let result = roulette.fire();
+        assert_eq!(result, FireResult::NoBullets);
+        assert_eq!(
+            outcome_reply(&config, result, "Ferris"),
+            "Click? The gun is somehow empty..."
+        );
+
+        let jammy = RouletteConfig {
+            jam_probability: 1.0,
+            ..Default::default()
+        };
+        let result = jammy.start().unwrap().fire();
+        assert_eq!(result, FireResult::Jammed);
+        assert_eq!(
+            outcome_reply(&config, result, "Ferris"),
+            "Click? You're lucky that the gun got jammed."
+        );
+    }
+
+    #[test]
+    fn test_settle_survivors() {
+        // Only bullets are left from the start
+        let config = RouletteConfig {
+            chambers: 1,
+            bullets: 1,
+            ..Default::default()
+        };
+        let mut roulette = config.clone().start().unwrap();
+        roulette.record_player(1, "Ferris", false);
+        roulette.record_player(2, "Corro", false);
+        // Disabled by default
+        assert_eq!(settle_survivors(&mut roulette, &Triggers::default()), None);
+
+        let winning = RouletteConfig {
+            survivors_win: true,
+            ..config
+        };
+        roulette.reconfigure(winning).unwrap();
+        assert_eq!(
+            settle_survivors(&mut roulette, &Triggers::default()).unwrap(),
+            format!(
+                "Only bullets are left! Ferris, Corro survived this loading and win. {}",
+                reloaded_reply(&roulette)
+            )
+        );
+        assert_eq!(roulette.stats().reloads, 1);
+        assert!(roulette.survivors().is_empty());
+        assert_eq!(settle_survivors(&mut roulette, &Triggers::default()), None);
+    }
+
+    #[test]
+    fn test_pull_outcomes() {
+        let config = RouletteConfig::default();
+        let player = Shooter {
+            user_id: 1,
+            name: "Ferris",
+            is_admin: false,
+        };
+        let shot = FireResult::Bullet {
+            duration: 60,
+            until: 60,
+        };
+        let bang = "Bang! Ferris was shot and muted for 60s, until 00:01 UTC.";
+        assert_eq!(
+            pull_outcomes(&config, &[shot], &player, String::new(), None),
+            [
+                CommandOutcome::Restrict {
+                    user_id: 1,
+                    name: "Ferris".to_string(),
+                    until: 60,
+                },
+                CommandOutcome::Reply(bang.to_string()),
+            ]
+        );
+
+        // Admins are not restricted, but told so
+        let admin = Shooter {
+            is_admin: true,
+            ..player
+        };
+        assert_eq!(
+            pull_outcomes(&config, &[shot], &admin, " Reloaded.".to_string(), None),
+            [CommandOutcome::Reply(format!(
+                "{bang} But admins cannot be muted. Reloaded."
+            ))]
+        );
+
+        // Nobody is restricted by a warning shot
+        assert_eq!(
+            pull_outcomes(
+                &config,
+                &[FireResult::Empty, FireResult::WarningShot],
+                &player,
+                String::new(),
+                None
+            ),
+            [CommandOutcome::Reply(
+                "Click... Bang! Just a warning shot past Ferris for now, as the game has only just started."
+                    .to_string()
+            )]
+        );
+
+        // The reload announcement follows the reply
+        let announcement = Some("Reloaded.".to_string());
+        assert_eq!(
+            pull_outcomes(
+                &config,
+                &[FireResult::Empty],
+                &admin,
+                String::new(),
+                announcement
+            ),
+            [
+                CommandOutcome::Reply("Click! Ferris is safe and sound.".to_string()),
+                CommandOutcome::Send("Reloaded.".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_narration() {
+        let config = RouletteConfig::default();
+        let admin = Shooter {
+            user_id: 1,
+            name: "Ferris",
+            is_admin: true,
+        };
+        let narrate = |results: &[FireResult]| match &pull_outcomes(
+            &config,
+            results,
+            &admin,
+            String::new(),
+            None,
+        )[..]
+        {
+            [CommandOutcome::Reply(text)] => text.clone(),
+            outcomes => panic!("Unexpected outcomes: {outcomes:?}"),
+        };
+        let shot = FireResult::Bullet {
+            duration: 180,
+            until: 180,
+        };
+        assert_eq!(
+            narrate(&[FireResult::Empty, FireResult::Empty, shot]),
+            "Click... Click... Bang! Ferris was shot and muted for 180s, until 00:03 UTC. But admins cannot be muted."
+        );
+        assert_eq!(
+            narrate(&[FireResult::Empty, FireResult::Empty]),
+            "Click... Click! Ferris is safe and sound."
+        );
+        assert_eq!(
+            narrate(&[FireResult::NoBullets]),
+            "Click? The gun is somehow empty..."
+        );
+    }
+
+    #[test]
+    fn test_parse_arg() {
+        assert_eq!(parse_arg(""), (DEFAULT_GUN, 1));
+        assert_eq!(parse_arg("3"), (DEFAULT_GUN, 3));
+        assert_eq!(parse_arg("left"), ("left", 1));
+        assert_eq!(parse_arg("left 2"), ("left", 2));
+        assert_eq!(parse_arg("big gun"), ("big gun", 1));
+    }
+
+    #[test]
+    fn test_rejection() {
+        use frankenstein::types::{
+            ChatMemberAdministrator, ChatMemberLeft, ChatMemberMember, ChatMemberOwner, User,
+        };
+        let user = User::builder()
+            .id(1)
+            .is_bot(false)
+            .first_name("Ferris")
+            .build();
+        let owner = ChatMember::Creator(
+            ChatMemberOwner::builder()
+                .user(user.clone())
+                .is_anonymous(false)
+                .build(),
+        );
+        let admin = ChatMember::Administrator(
+            ChatMemberAdministrator::builder()
+                .user(user.clone())
+                .can_be_edited(false)
+                .is_anonymous(false)
+                .can_manage_chat(false)
+                .can_delete_messages(false)
+                .can_manage_video_chats(false)
+                .can_restrict_members(false)
+                .can_promote_members(false)
+                .can_change_info(false)
+                .can_invite_users(false)
+                .can_post_stories(false)
+                .can_edit_stories(false)
+                .can_delete_stories(false)
+                .build(),
+        );
+        let member = ChatMember::Member(ChatMemberMember::builder().user(user.clone()).build());
+        let left = ChatMember::Left(ChatMemberLeft::builder().user(user).build());
+
+        let admin_rejected = Some(MessageKey::AdminCannotPlay);
+        assert_eq!(rejection(&owner, false), admin_rejected);
+        assert_eq!(rejection(&admin, false), admin_rejected);
+        assert_eq!(rejection(&member, false), None);
+
+        assert_eq!(rejection(&owner, true), Some(MessageKey::OwnerCannotPlay));
+        assert_eq!(rejection(&admin, true), None);
+        assert_eq!(rejection(&member, true), None);
+        assert_eq!(rejection(&left, true), Some(MessageKey::NotMember));
+    }
+
+    #[test]
+    fn test_language() {
+        let default: RouletteConfig =
+            toml::from_str("[messages]\njammed = \"{name} got lucky.\"").unwrap();
+        let resolve = |content: &str| {
+            toml::from_str::<GroupConfig>(content)
+                .unwrap()
+                .resolve(&default)
+                .unwrap()
+        };
+        let english = resolve("id = 1");
+        let russian = resolve("id = 2\nlanguage = \"ru\"");
+        let custom = resolve("id = 3\nlanguage = \"ru\"\n[messages]\nsafe = \"{name} lives!\"");
+
+        let reply = |config| outcome_reply(config, FireResult::Empty, "Ferris");
+        assert_eq!(reply(&english), "Click! Ferris is safe and sound.");
+        assert_eq!(reply(&russian), "Щёлк! Ferris цел(а) и невредим(а).");
+        assert_eq!(reply(&custom), "Ferris lives!");
+        // Templates of the game config are kept for the keys the group leaves out
+        assert_eq!(
+            outcome_reply(&custom, FireResult::Jammed, "Ferris"),
+            "Ferris got lucky."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_milestone() {
+        let config = RouletteConfig {
+            chambers: 6,
+            bullets: 1,
+            jam_probability: 0.0,
+            milestones: vec![100, 1_000],
+            ..Default::default()
+        };
+        let mut roulette = config.start().unwrap();
+        let mut state = roulette.snapshot();
+        state.stats.fires = 98;
+        roulette.restore(&state).unwrap();
+        let group = Mutex::new(Group::new(roulette));
+
+        let mut announcements = Vec::new();
+        for _ in 0..5 {
+            let msg = message("/roulette");
+            let api = context(MockApi::default().with_member("member"));
+            for outcome in RouletteCommand::execute(&api, msg, "", &group).await {
+                if let CommandOutcome::Send(text) = outcome {
+                    announcements.push(text);
+                }
+            }
+        }
+        assert_eq!(group.lock().await.stats().fires, 103);
+        assert_eq!(
+            announcements,
+            ["🎉 The trigger has been pulled 100 times in this group!"]
+        );
+        assert_eq!(crossed_milestone(&[100, 1_000], 90, 1_005), Some(1_000));
+        assert_eq!(crossed_milestone(&[100], 100, 101), None);
+    }
+
+    #[tokio::test]
+    async fn test_refire_on_empty() {
+        let config = RouletteConfig {
+            chambers: 1,
+            bullets: 1,
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        // Left empty, e.g. by a restored state or a reconfiguration
+        let mut roulette = config.start().unwrap();
+        roulette.fire();
+        assert!(roulette.is_empty());
+        let group = Mutex::new(Group::new(roulette));
+        let msg = message("/roulette");
+        let api = context(MockApi::default().with_member("member"));
+
+        // Reloaded and fired once more within the same turn, applying its outcome
+        let outcomes = RouletteCommand::execute(&api, msg, "", &group).await;
+        assert!(
+            matches!(
+                outcomes.first(),
+                Some(CommandOutcome::Restrict { user_id: 3, .. })
+            ),
+            "{outcomes:?}"
+        );
+        let stats = group.lock().await.stats();
+        assert_eq!((stats.fires, stats.bullets), (2, 2));
+        // Once for the refire, then again after the last bullet
+        assert_eq!(stats.reloads, 2);
+    }
+
+    #[tokio::test]
+    async fn test_reload_tip() {
+        let reply_on_last_chamber = async |show_reload_tip, auto_reload| {
+            let config = RouletteConfig {
+                chambers: 1,
+                bullets: 1,
+                jam_probability: 0.0,
+                min_mute_time: 60,
+                max_mute_time: 60,
+                show_reload_tip,
+                auto_reload,
+                ..Default::default()
+            };
+            let group = Mutex::new(Group::new(config.start().unwrap()));
+            let msg = message("/roulette");
+            let api = context(MockApi::default().with_member("member"));
+            let outcomes = RouletteCommand::execute(&api, msg, "", &group).await;
+            let Some(CommandOutcome::Reply(text)) = outcomes.last() else {
+                panic!("Expected a reply last: {outcomes:?}");
+            };
+            text.clone()
+        };
+        let reloaded = "The gun has been reloaded, with 1 bullets in 1 chambers.";
+        let emptied = "The gun is empty now, until an admin sends /reload.";
+
+        let text = reply_on_last_chamber(true, true).await;
+        assert!(
+            text.starts_with("Bang!") && text.ends_with(reloaded),
+            "{text}"
+        );
+        let text = reply_on_last_chamber(true, false).await;
+        assert!(text.ends_with(emptied), "{text}");
+        // Just the result without the tip
+        for auto_reload in [true, false] {
+            let text = reply_on_last_chamber(false, auto_reload).await;
+            assert!(text.starts_with("Bang!") && text.ends_with("."), "{text}");
+            assert!(
+                !text.contains(reloaded) && !text.contains(emptied),
+                "{text}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_manual_reload() {
+        let config = RouletteConfig {
+            chambers: 1,
+            bullets: 1,
+            jam_probability: 0.0,
+            auto_reload: false,
+            ..Default::default()
+        };
+        let group = Mutex::new(Group::new(config.start().unwrap()));
+        let msg = message("/roulette");
+        let api = context(MockApi::default().with_member("member"));
+
+        let outcomes = RouletteCommand::execute(&api, msg.clone(), "", &group).await;
+        let Some(CommandOutcome::Reply(text)) = outcomes.last() else {
+            panic!("Expected a reply last: {outcomes:?}");
+        };
+        assert!(text.ends_with(" The gun is empty now, until an admin sends /reload."));
+
+        // Stays empty, without wasting the turns
+        for _ in 0..3 {
+            let outcomes = RouletteCommand::execute(&api, msg.clone(), "", &group).await;
+            assert_eq!(
+                outcomes,
+                reply("Click... The gun is empty. An admin needs to /reload it.".to_string())
+            );
         }
+        let group = group.lock().await;
+        assert!(group.roulette().is_empty());
+        assert_eq!(group.roulette().stats().reloads, 0);
+        assert_eq!(group.roulette().stats().bullets, 1);
+    }
+
+    #[tokio::test]
+    async fn test_jam_cooldown() {
+        let config = RouletteConfig {
+            jam_probability: 1.0,
+            jam_cooldown_secs: 60,
+            ..Default::default()
+        };
+        let group = Mutex::new(Group::new(config.start().unwrap()));
+        let api = context(MockApi::default().with_member("member"));
+        let outcomes = RouletteCommand::execute(&api, message("/roulette"), "", &group).await;
+        assert_eq!(
+            outcomes,
+            reply("Click? You're lucky that the gun got jammed.".to_string())
+        );
+
+        // Refused while the gun is being cleared, without pulling the trigger
+        let outcomes = RouletteCommand::execute(&api, message("/roulette"), "", &group).await;
+        assert_eq!(
+            outcomes,
+            reply("The gun is jammed, clearing it... Try again in 60s.".to_string())
+        );
+        assert_eq!(group.lock().await.stats().fires, 1);
+
+        // Fired again once cleared
+        group.lock().await.clear_jam(None, DEFAULT_GUN);
+        RouletteCommand::execute(&api, message("/roulette"), "", &group).await;
+        assert_eq!(group.lock().await.stats().fires, 2);
+    }
+
+    #[tokio::test]
+    async fn test_russian() {
+        let config = RouletteConfig {
+            language: Locale::Ru,
+            jam_probability: 1.0,
+            jam_cooldown_secs: 60,
+            ..Default::default()
+        };
+        let group = Mutex::new(Group::new(config.start().unwrap()));
+        let owner = context(MockApi::default().with_member("creator"));
+        let outcomes = RouletteCommand::execute(&owner, message("/roulette"), "", &group).await;
+        assert_eq!(
+            outcomes,
+            reply("Админы не могут играть в рулетку".to_string())
+        );
+
+        let player = context(MockApi::default().with_member("member"));
+        let outcomes =
+            RouletteCommand::execute(&player, message("/roulette"), "left", &group).await;
+        assert_eq!(
+            outcomes,
+            reply("Револьвера с именем \"left\" нет. Доступные револьверы: default.".to_string())
+        );
+        RouletteCommand::execute(&player, message("/roulette"), "", &group).await;
+        let outcomes = RouletteCommand::execute(&player, message("/roulette"), "", &group).await;
+        assert_eq!(
+            outcomes,
+            reply("Револьвер заклинило, его чистят... Попробуй снова через 60 с.".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_jackpot() {
+        let config = RouletteConfig {
+            chambers: 6,
+            bullets: 1,
+            jam_probability: 0.0,
+            misfire_probability: 0.0,
+            jackpot_streak: 1,
+            ..Default::default()
+        };
+        // The next chamber is empty
+        let mut roulette = config.clone().start().unwrap();
+        let mut state = roulette.snapshot();
+        state.contents = vec![false, false, false, false, false, true];
+        roulette.restore(&state).unwrap();
+        let group = Mutex::new(Group::new(roulette));
+        let api = context(MockApi::default().with_member("member"));
+        let outcomes = RouletteCommand::execute(&api, message("/roulette"), "", &group).await;
+
+        // Announced after the reply, and pinned once the lock is released
+        assert_eq!(
+            outcomes,
+            [
+                CommandOutcome::Reply("Click! Ferris is safe and sound.".to_string()),
+                CommandOutcome::SendPinned(
+                    "Jackpot! Ferris has survived 1 pulls in a row!".to_string()
+                ),
+            ]
+        );
+        assert_eq!(api.bot.methods(), ["getChatMember"]);
+
+        // Reacted to first, after the config of the gun that fired, once the lock is released
+        let reactions = toml::from_str("empty = \"🎉\"").unwrap();
+        let config = RouletteConfig {
+            reactions: Some(reactions),
+            ..config
+        };
+        group.lock().await.reconfigure(&config).unwrap();
+        let outcomes = RouletteCommand::execute(&api, message("/roulette"), "", &group).await;
+        assert_eq!(outcomes[0], CommandOutcome::React("🎉".to_string()));
+        assert_eq!(api.bot.methods(), ["getChatMember", "getChatMember"]);
+    }
+
+    #[tokio::test]
+    async fn test_warning_shot() {
+        let config = RouletteConfig {
+            chambers: 1,
+            bullets: 1,
+            jam_probability: 0.0,
+            grace_period_secs: 300,
+            ..Default::default()
+        };
+        let mut group = Group::new(config.start().unwrap());
+        group.activate(Timestamp::now().as_second() as u64);
+        let group = Mutex::new(group);
+        let api = context(MockApi::default().with_member("member"));
+        let outcomes = RouletteCommand::execute(&api, message("/roulette"), "", &group).await;
+        let Some(CommandOutcome::Reply(text)) = outcomes.first() else {
+            panic!("Expected only a reply first: {outcomes:?}");
+        };
+        assert!(
+            text.starts_with("Bang! Just a warning shot past "),
+            "{text}"
+        );
+
+        // Fired, but counted as neither a bullet nor a loss
+        let group = group.lock().await;
+        let stats = group.stats();
+        assert_eq!((stats.fires, stats.bullets, stats.mute_secs), (1, 0, 0));
+        assert!(group.user_stats().is_empty());
+        assert_eq!(group.roulette().stats().reloads, 1);
     }
 }
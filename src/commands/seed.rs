@@ -0,0 +1,43 @@
+use super::{
+    Api, Command, CommandOutcome, Context, Group, gun_name, is_admin, message, no_such_gun, render,
+    reply, topic_id,
+};
+use crate::locale::MessageKey;
+use frankenstein::types::Message;
+use tokio::sync::Mutex;
+
+/// Show the seed of the current loading and the number of chambers fired, so as to replay the game. Takes an optional gun name.
+pub struct SeedCommand;
+
+impl Command for SeedCommand {
+    const TRIGGER: &'static str = "seed";
+    const HELP: &'static str =
+        "Show the seed of the current loading and the number of chambers fired. Admin only.";
+    async fn execute(
+        context: &Context<impl Api>,
+        msg: Message,
+        arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let bot = context.bot.as_ref();
+        let Some(admin) = is_admin(bot, &msg).await else {
+            return Vec::new();
+        };
+        if !admin {
+            return reply(message(&*group.lock().await, MessageKey::SeedAdminOnly));
+        }
+        let gun = gun_name(arg);
+        let group = group.lock().await;
+        let Some(roulette) = group.gun(topic_id(&msg), gun) else {
+            return reply(no_such_gun(&group, gun));
+        };
+        let (seed, position) = (roulette.seed(), roulette.position());
+        reply(render(
+            roulette.config().message(MessageKey::Seed),
+            &[
+                ("seed", &seed.to_string()),
+                ("position", &position.to_string()),
+            ],
+        ))
+    }
+}
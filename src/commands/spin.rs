@@ -0,0 +1,49 @@
+use super::{
+    Api, Command, CommandOutcome, Context, Group, get_member, is_admin_member, is_current_member,
+    message, paused, render, reply, topic_id,
+};
+use crate::locale::MessageKey;
+use frankenstein::types::Message;
+use tokio::sync::Mutex;
+
+/// Spin the cylinder, reshuffling the remaining bullets without firing.
+pub struct SpinCommand;
+
+impl Command for SpinCommand {
+    const TRIGGER: &'static str = "spin";
+    const HELP: &'static str =
+        "Spin the cylinder, reshuffling the remaining bullets without firing.";
+    async fn execute(
+        context: &Context<impl Api>,
+        msg: Message,
+        _arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let bot = context.bot.as_ref();
+        // Determine sender's role
+        let Some(member) = get_member(bot, &msg).await else {
+            return Vec::new();
+        };
+        if is_admin_member(&member) {
+            return reply(message(&*group.lock().await, MessageKey::SpinAdminOnly));
+        }
+        if !is_current_member(&member) {
+            return reply(message(&*group.lock().await, MessageKey::NotMember));
+        }
+
+        // Spin the roulette
+        let mut group = group.lock().await;
+        let Some(roulette) = group.playable(topic_id(&msg)) else {
+            return reply(paused(&group));
+        };
+        roulette.spin();
+        let (bullets, chambers) = roulette.peek();
+        reply(render(
+            roulette.config().message(MessageKey::Spun),
+            &[
+                ("bullets", &bullets.to_string()),
+                ("chambers", &chambers.to_string()),
+            ],
+        ))
+    }
+}
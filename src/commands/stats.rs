@@ -0,0 +1,62 @@
+use super::{Api, Command, CommandOutcome, Context, Group, render, reply};
+use crate::{RouletteConfig, Stats, locale::MessageKey};
+use frankenstein::types::Message;
+use tokio::sync::Mutex;
+
+/// Show the statistics of the game in this group.
+pub struct StatsCommand;
+
+impl Command for StatsCommand {
+    const TRIGGER: &'static str = "stats";
+    const HELP: &'static str = "Show the statistics of the game in this group.";
+    const INFORMATIONAL: bool = true;
+    async fn execute(
+        _context: &Context<impl Api>,
+        _msg: Message,
+        _arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let group = group.lock().await;
+        reply(stats_reply(group.roulette().config(), group.stats()))
+    }
+}
+
+/// Compose the reply for the given statistics.
+fn stats_reply(config: &RouletteConfig, stats: Stats) -> String {
+    let Stats {
+        fires,
+        bullets,
+        jams,
+        reloads,
+        ..
+    } = stats;
+    render(
+        config.message(MessageKey::Stats),
+        &[
+            ("fires", &fires.to_string()),
+            ("bullets", &bullets.to_string()),
+            ("jams", &jams.to_string()),
+            ("reloads", &reloads.to_string()),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_reply() {
+        let stats = Stats {
+            fires: 10,
+            bullets: 3,
+            jams: 1,
+            reloads: 2,
+            mute_secs: 120,
+        };
+        assert_eq!(
+            stats_reply(&RouletteConfig::default(), stats),
+            "So far: 10 pulls, 3 shot, 1 jammed, 2 reloads."
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use super::{Api, Command, CommandOutcome, Context, Group, paused, reply, topic_id};
+use frankenstein::types::Message;
+use tokio::sync::Mutex;
+
+/// Show how many shots have been fired in the current loading.
+pub struct StatusCommand;
+
+impl Command for StatusCommand {
+    const TRIGGER: &'static str = "status";
+    const HELP: &'static str = "Show how many shots have been fired in the current loading.";
+    const INFORMATIONAL: bool = true;
+    async fn execute(
+        _context: &Context<impl Api>,
+        msg: Message,
+        _arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let mut group = group.lock().await;
+        let Some(roulette) = group.playable(topic_id(&msg)) else {
+            return reply(paused(&group));
+        };
+        let (shots, filled, left) = roulette.status();
+        reply(status_reply(shots, filled, left))
+    }
+}
+
+/// Compose the reply for the given status.
+fn status_reply(shots: usize, filled: usize, left: usize) -> String {
+    let plural = |count: usize| if count == 1 { "" } else { "s" };
+    format!(
+        "{shots} shot{} fired, {filled} live round{} among {left} remaining chamber{}.",
+        plural(shots),
+        plural(filled),
+        plural(left)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_reply() {
+        assert_eq!(
+            status_reply(3, 1, 3),
+            "3 shots fired, 1 live round among 3 remaining chambers."
+        );
+        assert_eq!(
+            status_reply(1, 2, 5),
+            "1 shot fired, 2 live rounds among 5 remaining chambers."
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use super::{
+    Api, Command, CommandOutcome, Context, Group, display_name, format_timestamp, get_member,
+    is_admin_member, is_current_member, message, paused, render, reply,
+};
+use crate::locale::MessageKey;
+use frankenstein::types::Message;
+use jiff::Timestamp;
+use log::error;
+use tokio::sync::Mutex;
+
+/// Surrender, muting yourself for the minimum mute time without touching the revolver.
+pub struct SurrenderCommand;
+
+impl Command for SurrenderCommand {
+    const TRIGGER: &'static str = "surrender";
+    const HELP: &'static str =
+        "Surrender, muting yourself for the minimum mute time without touching the revolver.";
+    async fn execute(
+        context: &Context<impl Api>,
+        msg: Message,
+        _arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let bot = context.bot.as_ref();
+        // Get sender
+        let Some(sender) = &msg.from else {
+            error!("Cannot determine sender of message: {msg:?}");
+            return Vec::new();
+        };
+        // Determine sender's role
+        let Some(member) = get_member(bot, &msg).await else {
+            return Vec::new();
+        };
+        if is_admin_member(&member) {
+            return reply(message(&*group.lock().await, MessageKey::SurrenderAdmin));
+        }
+        if !is_current_member(&member) {
+            return reply(message(&*group.lock().await, MessageKey::NotMember));
+        }
+        let name = display_name(sender);
+
+        // Only the config is read, leaving the revolver as is
+        let config = {
+            let group = group.lock().await;
+            if !group.is_enabled() {
+                return reply(paused(&group));
+            }
+            group.roulette().config().clone()
+        };
+        let duration = u64::from(config.min_mute_time());
+        let now = Timestamp::now().as_second() as u64;
+        let until = now.saturating_add(duration);
+
+        // Restrict the user, replying only if it succeeds
+        let time = format_timestamp(until, now, config.timezone());
+        let text = render(
+            config.message(MessageKey::Surrendered),
+            &[
+                ("name", &name),
+                ("duration", &duration.to_string()),
+                ("time", &time),
+            ],
+        );
+        vec![
+            CommandOutcome::Restrict {
+                user_id: sender.id,
+                name,
+                until,
+            },
+            CommandOutcome::Reply(text),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        RouletteConfig,
+        mock::{MockApi, SENDER, context, message},
+    };
+
+    #[tokio::test]
+    async fn test_surrender() {
+        let config = RouletteConfig {
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        let group = Mutex::new(Group::new(config.start().unwrap()));
+        group.lock().await.roulette_mut().fire();
+        let before = group.lock().await.roulette().clone();
+
+        let api = context(MockApi::default().with_member("member"));
+        let outcomes = SurrenderCommand::execute(&api, message("/surrender"), "", &group).await;
+        let [
+            CommandOutcome::Restrict { user_id, .. },
+            CommandOutcome::Reply(text),
+        ] = outcomes.as_slice()
+        else {
+            panic!("Expected a restriction and a reply: {outcomes:?}");
+        };
+        assert_eq!(*user_id, SENDER);
+        assert!(text.starts_with("Ferris waves a white flag"), "{text}");
+
+        // Neither the position nor the contents have changed
+        assert_eq!(*group.lock().await.roulette(), before);
+    }
+}
@@ -0,0 +1,198 @@
+use super::{
+    Api, Command, CommandOutcome, Commands, Context, Group, is_admin, message, paused, render,
+    reply,
+};
+use crate::{locale::MessageKey, tournament::Tournament};
+use frankenstein::types::Message;
+use log::error;
+use std::time::Duration;
+use tokio::{sync::Mutex, time::sleep};
+
+/// Start a tournament, which runs once the join window has closed.
+pub struct TournamentCommand;
+
+impl Command for TournamentCommand {
+    const TRIGGER: &'static str = "tournament";
+    const HELP: &'static str =
+        "Start a tournament, which runs once the join window has closed. Admin only.";
+    async fn execute(
+        context: &Context<impl Api>,
+        msg: Message,
+        _arg: &str,
+        group: &Mutex<Group>,
+    ) -> Vec<CommandOutcome> {
+        let bot = context.bot.as_ref();
+        let triggers = &context.triggers();
+        let Some(admin) = is_admin(bot, &msg).await else {
+            return Vec::new();
+        };
+        if !admin {
+            return reply(message(
+                &*group.lock().await,
+                MessageKey::TournamentAdminOnly,
+            ));
+        }
+
+        // Start a new tournament, unless one is accepting participants already
+        let (window, config) = {
+            let mut group = group.lock().await;
+            let Some(roulette) = group.playable(None) else {
+                return reply(paused(&group));
+            };
+            let config = roulette.config().clone();
+            let window = config.tournament_join_secs();
+            if let Some(tournament) = group.tournament() {
+                return reply(render(
+                    config.message(MessageKey::TournamentJoining),
+                    &[
+                        ("count", &tournament.participants().len().to_string()),
+                        (
+                            "seconds",
+                            &tournament.time_left().as_secs_f64().ceil().to_string(),
+                        ),
+                    ],
+                ));
+            }
+            *group.tournament() = Some(Tournament::new(Duration::from_secs(window)));
+            (window, config)
+        };
+        let text = render(
+            config.message(MessageKey::TournamentStarted),
+            &[
+                ("join", &triggers.command(Commands::Join)),
+                ("seconds", &window.to_string()),
+            ],
+        );
+        context.announce(&msg, text).await;
+
+        // Run the tournament with a fresh gun once the window closes
+        sleep(Duration::from_secs(window)).await;
+        let mut group = group.lock().await;
+        let Some(tournament) = group.tournament().take() else {
+            return Vec::new();
+        };
+        let Some(roulette) = group.playable(None) else {
+            let called_off = group.roulette().config().message(MessageKey::CalledOff);
+            return reply(format!("{} {called_off}", paused(&group)));
+        };
+        let config = roulette.config().clone();
+        let mut gun = match config.clone().start() {
+            Ok(gun) => gun,
+            Err(err) => {
+                error!("Failed to start the tournament gun: {err}");
+                return reply(config.message(MessageKey::TournamentBroken).to_string());
+            }
+        };
+        drop(group);
+        let result = tournament.run(&mut gun);
+        let mut outcomes = Vec::new();
+        let mut text = String::new();
+        for (round, eliminated) in result.rounds.iter().enumerate() {
+            let round = round + 1;
+            let round = round.to_string();
+            if eliminated.is_empty() {
+                text += &render(
+                    config.message(MessageKey::RoundSurvived),
+                    &[("round", &round)],
+                );
+                text.push('\n');
+                continue;
+            }
+            let names: Vec<_> = eliminated.iter().map(|(_, name)| name.as_str()).collect();
+            text += &render(
+                config.message(MessageKey::RoundShot),
+                &[("round", &round), ("names", &names.join(", "))],
+            );
+            text.push('\n');
+
+            // Mute the eliminated
+            for (user_id, name) in eliminated {
+                let (_, until) = gun.random_mute_until();
+                outcomes.push(CommandOutcome::Restrict {
+                    user_id: *user_id,
+                    name: name.clone(),
+                    until,
+                });
+            }
+        }
+        match result.champion {
+            Some((_, name)) => {
+                text += &render(config.message(MessageKey::Champion), &[("name", &name)]);
+            }
+            None if result.rounds.is_empty() => text += config.message(MessageKey::NobodyJoined),
+            None => text += config.message(MessageKey::Draw),
+        }
+
+        outcomes.push(CommandOutcome::Reply(text));
+        outcomes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        RouletteConfig,
+        commands::join::JoinCommand,
+        locale::Locale,
+        mock::{MockApi, context, message},
+    };
+
+    #[tokio::test]
+    async fn test_tournament() {
+        let config = RouletteConfig {
+            tournament_join_secs: 1,
+            ..Default::default()
+        };
+        let group = Mutex::new(Group::new(config.start().unwrap()));
+        let host = context(MockApi::default().with_member("creator").with_sending());
+        let player = context(MockApi::default().with_member("member"));
+
+        // Runs by itself once the window closes, after the players have joined
+        let (outcomes, joined) = tokio::join!(
+            TournamentCommand::execute(&host, message("/tournament"), "", &group),
+            async {
+                sleep(Duration::from_millis(100)).await;
+                let status =
+                    TournamentCommand::execute(&host, message("/tournament"), "", &group).await;
+                let joined = JoinCommand::execute(&player, message("/join"), "", &group).await;
+                (status, joined)
+            }
+        );
+        let announced = &host.bot.requests("sendMessage")[0];
+        assert_eq!(
+            announced["text"],
+            "A tournament has started! Send /join within 1s to take part."
+        );
+        assert_eq!(announced["reply_parameters"]["message_id"], 1);
+        let (status, joined) = joined;
+        assert_eq!(
+            status,
+            reply("0 players have joined the tournament, which starts in 1s.".to_string())
+        );
+        assert_eq!(
+            joined,
+            reply("Ferris has joined the tournament, as player #1.".to_string())
+        );
+        assert_eq!(outcomes, reply("Ferris is the champion!".to_string()));
+        assert!(group.lock().await.tournament().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_russian() {
+        let config = RouletteConfig {
+            language: Locale::Ru,
+            ..Default::default()
+        };
+        let group = Mutex::new(Group::new(config.start().unwrap()));
+        let player = context(MockApi::default().with_member("member"));
+        let outcomes =
+            TournamentCommand::execute(&player, message("/tournament"), "", &group).await;
+        assert_eq!(
+            outcomes,
+            reply("Только админы могут проводить турнир".to_string())
+        );
+        let outcomes = JoinCommand::execute(&player, message("/join"), "", &group).await;
+        assert_eq!(outcomes, reply("Сейчас турнира нет.".to_string()));
+    }
+}
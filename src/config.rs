@@ -0,0 +1,278 @@
+//! Parsing and merging of configuration files.
+
+use super::{Config, Mode, Triggers};
+use serde_json::Value;
+use std::path::Path;
+
+/// Environment variable supplying the bot token, taking precedence over the config files.
+pub const TOKEN_ENV: &str = "ROULETTE_BOT_TOKEN";
+
+/// Supported formats of the configuration file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// [TOML](https://toml.io/), the default.
+    Toml,
+    /// [JSON](https://www.json.org/).
+    Json,
+    /// [YAML](https://yaml.org/).
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// Detect the format from the extension of the given path, defaulting to TOML for extensionless paths.
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        let Some(extension) = path.extension() else {
+            return Ok(Self::Toml);
+        };
+        match extension.to_string_lossy().to_lowercase().as_str() {
+            "toml" => Ok(Self::Toml),
+            "json" => Ok(Self::Json),
+            "yaml" | "yml" => Ok(Self::Yaml),
+            other => Err(format!(
+                "Unsupported config file extension `.{other}`, expected one of `.toml`, `.json`, `.yaml` or `.yml`"
+            )),
+        }
+    }
+
+    /// Parse the given content in this format into a format-agnostic value.
+    fn parse_value(self, content: &str) -> Result<Value, String> {
+        match self {
+            Self::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            Self::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+            Self::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// Parse and merge multiple configuration files in order.
+///
+/// Later files take precedence over earlier ones:
+///
+/// - Tables (like `game`) are merged key by key, so a later file only needs to specify the keys it overrides.
+/// - Groups are merged by their `id`, instead of being appended. This also merges duplicate groups within a single file.
+/// - Any other value (like `token`) is replaced as a whole.
+pub fn merge<'a>(
+    sources: impl IntoIterator<Item = (ConfigFormat, &'a str)>,
+) -> Result<Config, String> {
+    let mut merged = Value::Object(Default::default());
+    let mut groups = Vec::new();
+    for (format, content) in sources {
+        let mut value = format.parse_value(content)?;
+        let Value::Object(table) = &mut value else {
+            return Err("Top level of the config must be a table".to_string());
+        };
+        match table.remove("groups") {
+            Some(Value::Array(overlay)) => {
+                for group in overlay {
+                    merge_group(&mut groups, group);
+                }
+            }
+            Some(_) => return Err("`groups` must be an array".to_string()),
+            None => {}
+        }
+        merge_value(&mut merged, value);
+    }
+    merged["groups"] = Value::Array(groups);
+    serde_json::from_value(merged).map_err(|e| e.to_string())
+}
+
+/// Resolve the bot token, preferring the non-empty value of [`TOKEN_ENV`] over the one in the config files.
+pub fn resolve_token(config: &mut Config, env: Option<String>) -> Result<(), String> {
+    if let Some(token) = env.filter(|token| !token.is_empty()) {
+        config.token = token;
+    }
+    if config.token.is_empty() {
+        return Err(format!(
+            "No bot token configured, set `token` in the config or `{TOKEN_ENV}` in the environment"
+        ));
+    }
+    Ok(())
+}
+
+/// Check the config as the bot would use it, returning the problems found, if any.
+pub fn check(config: &Config) -> Vec<String> {
+    let mut errors = Vec::new();
+    if let Err(err) = config.game.clone().start() {
+        errors.push(format!("Invalid game config: {err}"));
+    }
+    for group in &config.groups {
+        if let Err(err) = group.resolve(&config.game) {
+            errors.push(format!("Invalid config for group <{}>: {err}", group.id));
+        }
+    }
+    if let Err(err) = Triggers::new(&config.command_triggers) {
+        errors.push(err);
+    }
+    if let Err(err) = config.validate() {
+        errors.push(err);
+    }
+    if config.mode == Mode::Webhook {
+        if config.webhook_url.is_none() {
+            errors.push("`webhook_url` is required in webhook mode".to_string());
+        }
+        if config.http_addr.is_none() {
+            errors.push("`http_addr` is required in webhook mode".to_string());
+        }
+    }
+    errors
+}
+
+/// Merge `overlay` into `base`, recursively for tables.
+fn merge_value(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_value(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Merge a group into the list of groups, by its id.
+fn merge_group(groups: &mut Vec<Value>, group: Value) {
+    let id = group.get("id").cloned();
+    let existing = id
+        .as_ref()
+        .and_then(|id| groups.iter_mut().find(|g| g.get("id") == Some(id)));
+    match existing {
+        Some(existing) => merge_value(existing, group),
+        None => groups.push(group),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_path() {
+        let format = |path: &str| ConfigFormat::from_path(Path::new(path));
+        assert_eq!(format("config"), Ok(ConfigFormat::Toml));
+        assert_eq!(format("config.toml"), Ok(ConfigFormat::Toml));
+        assert_eq!(format("config.json"), Ok(ConfigFormat::Json));
+        assert_eq!(format("config.yaml"), Ok(ConfigFormat::Yaml));
+        assert_eq!(format("config.YML"), Ok(ConfigFormat::Yaml));
+        assert!(format("config.ini").is_err());
+    }
+
+    #[test]
+    fn test_formats() {
+        let sources = [
+            (ConfigFormat::Toml, "token = \"abc\"\n[[groups]]\nid = 1"),
+            (
+                ConfigFormat::Json,
+                r#"{"token": "abc", "groups": [{"id": 1}]}"#,
+            ),
+            (ConfigFormat::Yaml, "token: abc\ngroups:\n  - id: 1"),
+        ];
+        for source in sources {
+            let config = merge([source]).unwrap();
+            assert_eq!(config.token, "abc");
+            assert_eq!(config.groups[0].id, 1);
+        }
+
+        let config =
+            merge([(ConfigFormat::Toml, "token = \"abc\"\nstats_flush_secs = 30")]).unwrap();
+        assert_eq!(config.state_flush_secs, 30);
+    }
+
+    #[test]
+    fn test_merge() {
+        let base = r#"
+            token = "base"
+            [game]
+            chambers = 8
+            bullets = 3
+            [[groups]]
+            id = 1
+            bullets = 1
+            [[groups]]
+            id = 2
+        "#;
+        let overlay = r#"{
+            "token": "overlay",
+            "game": { "bullets": 4 },
+            "groups": [{ "id": 1, "chambers": 4 }, { "id": 3 }, { "id": 3, "bullets": 2 }]
+        }"#;
+        let config = merge([(ConfigFormat::Toml, base), (ConfigFormat::Json, overlay)]).unwrap();
+
+        assert_eq!(config.token, "overlay");
+        assert_eq!(config.game.info(), (4, 8));
+        let ids: Vec<_> = config.groups.iter().map(|g| g.id).collect();
+        assert_eq!(ids, [1, 2, 3]);
+        assert_eq!(
+            config.groups[0].resolve(&config.game).unwrap().info(),
+            (1, 4)
+        );
+        assert_eq!(
+            config.groups[2].resolve(&config.game).unwrap().info(),
+            (2, 8)
+        );
+    }
+
+    #[test]
+    fn test_invalid_toml() {
+        let unparseable = "token = \"abc\"\n[game\nchambers = 6";
+        let Err(err) = merge([(ConfigFormat::Toml, unparseable)]) else {
+            panic!("Unparseable TOML should be rejected");
+        };
+        // Points at the offending line
+        assert!(err.contains("line 2"), "{err}");
+
+        let mistyped = "token = \"abc\"\n[game]\nchambers = \"six\"";
+        let Err(err) = merge([(ConfigFormat::Toml, mistyped)]) else {
+            panic!("Mistyped values should be rejected");
+        };
+        assert!(err.contains("expected usize"), "{err}");
+    }
+
+    #[test]
+    fn test_check() {
+        let valid = merge([(ConfigFormat::Toml, "[[groups]]\nid = 1")]).unwrap();
+        assert!(check(&valid).is_empty());
+        let late = merge([(ConfigFormat::Toml, "digest_hour = 24")]).unwrap();
+        assert_eq!(check(&late), ["`digest_hour` must be between 0 and 23"]);
+
+        let invalid = merge([(
+            ConfigFormat::Toml,
+            "mode = \"webhook\"\nstats_reset_hour = 24\n[game]\nbullets = 0\n\
+            [[groups]]\nid = 1\nbullets = 1\n[[groups]]\nid = 2\nchambers = 0",
+        )])
+        .unwrap();
+        assert_eq!(
+            check(&invalid),
+            [
+                "Invalid game config: Number of bullets must be greater than 0",
+                "Invalid config for group <2>: Number of chambers must be greater than 0",
+                "`stats_reset_hour` must be between 0 and 23",
+                "`webhook_url` is required in webhook mode",
+                "`http_addr` is required in webhook mode",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_token() {
+        let from_file = || merge([(ConfigFormat::Toml, "token = \"file\"")]).unwrap();
+        let mut config = from_file();
+        resolve_token(&mut config, Some("env".to_string())).unwrap();
+        assert_eq!(config.token, "env");
+
+        for env in [None, Some(String::new())] {
+            let mut config = from_file();
+            resolve_token(&mut config, env).unwrap();
+            assert_eq!(config.token, "file");
+        }
+
+        let mut config = merge([(ConfigFormat::Toml, "")]).unwrap();
+        assert!(resolve_token(&mut config, None).is_err());
+        resolve_token(&mut config, Some("env".to_string())).unwrap();
+        assert_eq!(config.token, "env");
+    }
+}
@@ -1,6 +1,8 @@
 //! Constants for the bot.
 
+use super::{FireMode, PeekMode};
 use frankenstein::types::{ChatAdministratorRights, ChatPermissions};
+use std::time::Duration;
 
 /// Default number of chambers in the revolver.
 pub fn chambers() -> usize {
@@ -14,7 +16,17 @@ pub fn bullets() -> usize {
 
 /// Default probability of the gun getting jammed.
 pub fn jam_probability() -> f64 {
-    0.05 
+    0.05
+}
+
+/// Default increase of the jam probability per chamber fired since the last reload.
+pub fn jam_escalation() -> f64 {
+    0.0
+}
+
+/// Default probability of a bullet misfiring.
+pub fn misfire_probability() -> f64 {
+    0.0
 }
 
 /// Default minimum time to mute in seconds.
@@ -22,11 +34,179 @@ pub fn min_mute_time() -> u32 {
     60
 }
 
+/// Default of whether mute times escalate.
+pub fn escalating_mute() -> bool {
+    false
+}
+
 /// Default maximum time to mute in seconds.
 pub fn max_mute_time() -> u32 {
     600
 }
 
+/// Default minimum number of members for a group to be playable, `0` meaning no limit.
+pub fn min_members() -> u32 {
+    0
+}
+
+/// Default number of consecutive survivals for a jackpot, `0` meaning disabled.
+pub fn jackpot_streak() -> usize {
+    0
+}
+
+/// Default of whether to announce reloads in a standalone message.
+pub fn announce_reload() -> bool {
+    false
+}
+
+/// Default of whether the reload tip is shown.
+pub fn show_reload_tip() -> bool {
+    true
+}
+
+/// Default of whether to reload the gun once no bullets are left.
+pub fn auto_reload() -> bool {
+    true
+}
+
+/// Default time window in seconds for backing out of a pull with `/chicken`, `0` meaning disabled.
+pub fn chicken_window_secs() -> u64 {
+    0
+}
+
+/// Default seconds for clearing a jammed gun, during which it cannot be fired, `0` meaning disabled.
+pub fn jam_cooldown_secs() -> u64 {
+    0
+}
+
+/// Default of whether a jam skips the current chamber, i.e. no, so the same chamber is retried.
+pub fn jam_advances() -> bool {
+    false
+}
+
+/// Default seconds a user has to wait between two plays, `0` meaning disabled.
+pub fn cooldown_secs() -> u64 {
+    0
+}
+
+/// Default grace period in seconds, disabled.
+pub fn grace_period_secs() -> u64 {
+    0
+}
+
+/// Default of whether admins other than the owner may play.
+pub fn admins_can_play() -> bool {
+    false
+}
+
+/// Default of whether `/debug` is enabled.
+pub fn debug_enabled() -> bool {
+    false
+}
+
+/// Default of whether each topic of a forum supergroup has its own game.
+pub fn per_topic() -> bool {
+    false
+}
+
+/// Default of whether players surviving a loading win once only bullets are left in it.
+pub fn survivors_win() -> bool {
+    false
+}
+
+/// Default mode of deciding bullets, i.e. a fixed loading.
+pub fn mode() -> FireMode {
+    FireMode::Fixed
+}
+
+/// Default of what `/peek` reveals, i.e. the counts of filled and left chambers.
+pub fn peek_mode() -> PeekMode {
+    PeekMode::Counts
+}
+
+/// Default probability of peeking discharging the gun.
+pub fn peek_fire_probability() -> f64 {
+    0.0
+}
+
+/// Default of whether peeking tells the jam probability.
+pub fn peek_shows_jam() -> bool {
+    false
+}
+
+/// Default timezone for displaying times.
+pub fn timezone() -> String {
+    "UTC".to_string()
+}
+
+/// Default time window in seconds for joining a tournament.
+pub fn tournament_join_secs() -> u64 {
+    60
+}
+
+/// Default maximum seconds since the last successful poll, for the bot to be considered healthy.
+pub fn health_threshold_secs() -> u64 {
+    120
+}
+
+/// Default reaction when a player got shot. Note that Telegram only accepts a [fixed set of emoji](https://core.telegram.org/bots/api#reactiontypeemoji).
+pub fn bullet_reaction() -> String {
+    "🤯".to_string()
+}
+
+/// Default reaction when a player survived.
+pub fn empty_reaction() -> String {
+    "😎".to_string()
+}
+
+/// Default reaction when the gun got jammed.
+pub fn jammed_reaction() -> String {
+    "🤨".to_string()
+}
+
+/// Default path to receive updates at in webhook mode.
+pub fn webhook_path() -> String {
+    "/webhook".to_string()
+}
+
+/// Default reply to messages in private chats.
+pub fn private_reply() -> String {
+    "I only work in configured groups. Add me to one and ask the host to whitelist it!".to_string()
+}
+
+/// Default seconds between flushes of the state file.
+pub fn state_flush_secs() -> u64 {
+    10
+}
+
+/// Maximum mute time in seconds (365 days), within the 366 days that Telegram permits for `until_date`.
+pub const MAX_MUTE_TIME: u32 = 31_536_000;
+
+/// Seconds added to the end of each mute, so that it still exceeds the 30 seconds Telegram
+/// requires by the time a slow request arrives, instead of counting as a permanent ban.
+pub const MUTE_MARGIN_SECS: u64 = 5;
+
+/// Delay before retrying the first failed request to Telegram.
+pub const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Maximum delay before retrying failed requests to Telegram.
+pub const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Number of messages that can be sent to a chat at once, before being delayed.
+pub const CHAT_BURST: u32 = 20;
+
+/// Interval between messages to a chat beyond the burst, i.e. 20 messages per minute that Telegram permits in groups.
+pub const CHAT_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Maximum number of retries of a message rejected for flooding.
+pub const MAX_SEND_RETRIES: usize = 3;
+
+/// Maximum length of user names in replies, in characters.
+pub const MAX_NAME_LENGTH: usize = 32;
+
+/// Number of users shown by `/leaderboard`.
+pub const LEADERBOARD_SIZE: usize = 10;
+
 /// Restricted permissions when someone got shot.
 pub const RESTRICTED_PERM: ChatPermissions = ChatPermissions {
     can_send_messages: Some(false),
@@ -45,6 +225,24 @@ pub const RESTRICTED_PERM: ChatPermissions = ChatPermissions {
     can_manage_topics: None,
 };
 
+/// Permissions lifting the restriction, e.g. when someone is pardoned, complementary to [`RESTRICTED_PERM`].
+pub const UNRESTRICTED_PERM: ChatPermissions = ChatPermissions {
+    can_send_messages: Some(true),
+    can_send_audios: Some(true),
+    can_send_documents: Some(true),
+    can_send_photos: Some(true),
+    can_send_videos: Some(true),
+    can_send_video_notes: Some(true),
+    can_send_voice_notes: Some(true),
+    can_send_polls: Some(true),
+    can_send_other_messages: Some(true),
+    can_add_web_page_previews: None,
+    can_change_info: None,
+    can_invite_users: None,
+    can_pin_messages: None,
+    can_manage_topics: None,
+};
+
 /// Recommended admin rights for the bot.
 pub const RECOMMENDED_ADMIN_RIGHTS: ChatAdministratorRights = ChatAdministratorRights {
     is_anonymous: false,
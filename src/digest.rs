@@ -0,0 +1,168 @@
+//! Weekly digest of the activity in each group.
+
+use super::{Group, RouletteConfig, Stats, UserStats, commands::render, locale::MessageKey, reset};
+use frankenstein::{AsyncTelegramApi, client_reqwest::Bot, methods::SendMessageParams};
+use jiff::Timestamp;
+use log::{error, info};
+use serde::Deserialize;
+use std::{collections::HashMap, time::Duration};
+use tokio::{sync::Mutex, time::interval};
+
+/// Seconds in a week.
+const WEEK_SECS: u64 = 7 * reset::DAY_SECS;
+/// How often to check whether the digest is due.
+const CHECK_PERIOD: Duration = Duration::from_secs(60);
+
+/// Day of the week.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    /// Days from Thursday, the weekday of the epoch.
+    fn days_since_epoch_weekday(self) -> u64 {
+        (self as u64 + 4) % 7
+    }
+}
+
+/// Whether the weekly digest on `weekday` at `hour` UTC is due at the UNIX timestamp `now`, having last checked at `last`.
+pub fn should_post(last: u64, now: u64, weekday: Weekday, hour: u8) -> bool {
+    let offset = weekday.days_since_epoch_weekday() * reset::DAY_SECS + u64::from(hour) * 3600;
+    reset::crossed(last, now, offset, WEEK_SECS)
+}
+
+/// Compose the digest of the week's statistics of a group in its language, naming the player shot
+/// the most so far, if any.
+pub fn digest(config: &RouletteConfig, week: Stats, users: &HashMap<u64, UserStats>) -> String {
+    let Stats {
+        fires,
+        bullets,
+        mute_secs,
+        ..
+    } = week;
+    let survivals = fires - bullets;
+    let mut text = render(
+        config.message(MessageKey::Digest),
+        &[
+            ("fires", &fires.to_string()),
+            ("bullets", &bullets.to_string()),
+            ("survivals", &survivals.to_string()),
+            ("mute_secs", &mute_secs.to_string()),
+        ],
+    );
+    let loser = users
+        .values()
+        .filter(|user| user.shot > 0)
+        .max_by(|a, b| a.shot.cmp(&b.shot).then_with(|| b.name.cmp(&a.name)));
+    if let Some(loser) = loser {
+        let loser = render(
+            config.message(MessageKey::DigestLoser),
+            &[("name", &loser.name), ("shot", &loser.shot.to_string())],
+        );
+        text += &format!(" {loser}");
+    }
+    text
+}
+
+/// Post the digest of the past week to each group with any activity, weekly on `weekday` at
+/// `hour` UTC, never returning.
+pub async fn run(bot: &Bot, group_data: &HashMap<i64, Mutex<Group>>, weekday: Weekday, hour: u8) {
+    // Statistics at the last digest, or at startup
+    let mut previous = HashMap::with_capacity(group_data.len());
+    for (&group_id, group) in group_data {
+        previous.insert(group_id, group.lock().await.stats());
+    }
+    let mut interval = interval(CHECK_PERIOD);
+    let mut last = Timestamp::now().as_second() as u64;
+    loop {
+        interval.tick().await;
+        let now = Timestamp::now().as_second() as u64;
+        if should_post(last, now, weekday, hour) {
+            info!("Posting the weekly digest");
+            for (&group_id, group) in group_data {
+                let text = {
+                    let group = group.lock().await;
+                    let stats = group.stats();
+                    let earlier = previous.insert(group_id, stats).unwrap_or_default();
+                    let week = stats.since(earlier);
+                    (week.fires > 0)
+                        .then(|| digest(group.roulette().config(), week, group.user_stats()))
+                };
+                if let Some(text) = text {
+                    post(bot, group_id, text).await;
+                }
+            }
+        }
+        last = now;
+    }
+}
+
+/// Post the digest in the group.
+async fn post(bot: &Bot, group_id: i64, text: String) {
+    let send_message_param = SendMessageParams::builder()
+        .chat_id(group_id)
+        .text(text)
+        .build();
+    if let Err(err) = bot.send_message(&send_message_param).await {
+        error!("Failed to post weekly digest in group <{group_id}>: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_post() {
+        let day = |d: u64| d * reset::DAY_SECS;
+        // The epoch is a Thursday, so the first Monday is on the 5th
+        assert!(!should_post(0, day(4) - 1, Weekday::Monday, 0));
+        assert!(should_post(0, day(4), Weekday::Monday, 0));
+        assert!(!should_post(day(4), day(11) - 1, Weekday::Monday, 0));
+        assert!(should_post(day(4), day(11), Weekday::Monday, 0));
+        assert!(should_post(day(7) - 1, day(7) + 3600, Weekday::Thursday, 1));
+        assert!(!should_post(day(7) + 3600, day(8), Weekday::Thursday, 1));
+    }
+
+    #[test]
+    fn test_digest() {
+        let week = Stats {
+            fires: 20,
+            bullets: 4,
+            jams: 1,
+            reloads: 2,
+            mute_secs: 900,
+        };
+        let user = |name: &str, shot| UserStats {
+            name: name.to_string(),
+            shot,
+            survived: 5,
+        };
+        let users = HashMap::from([(1, user("Ferris", 3)), (2, user("Corro", 1))]);
+        let config = RouletteConfig::default();
+        assert_eq!(
+            digest(&config, week, &users),
+            "📊 This week: 20 pulls, 4 shot, 16 survived, 900s of mutes dealt. Biggest loser so far: Ferris, shot 3 times."
+        );
+        // Nobody shot yet
+        let users = HashMap::from([(1, user("Ferris", 0))]);
+        assert_eq!(
+            digest(&config, week, &users),
+            "📊 This week: 20 pulls, 4 shot, 16 survived, 900s of mutes dealt."
+        );
+        // In the language of the group
+        let russian = RouletteConfig {
+            language: crate::locale::Locale::Ru,
+            ..Default::default()
+        };
+        assert!(digest(&russian, week, &users).starts_with("📊 За неделю: выстрелов 20"));
+    }
+}
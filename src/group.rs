@@ -0,0 +1,770 @@
+//! Per-group state.
+
+use super::{
+    ConfigError, Roulette, RouletteConfig, Stats,
+    state::{GroupState, RouletteState, TopicState},
+    tournament::Tournament,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+/// Name of the gun used when none is given.
+pub const DEFAULT_GUN: &str = "default";
+
+/// State of a group.
+///
+/// With `per_topic`, each forum topic has revolvers of its own, while whether the game is enabled, the cooldowns and the records of users stay shared by the whole group.
+#[derive(Clone, Debug)]
+pub struct Group {
+    /// The revolvers played outside of topics.
+    guns: Guns,
+    /// Mapping topic ID to the revolvers played in it with `per_topic`, started on first use.
+    topics: HashMap<i32, Guns>,
+    /// Whether the game is enabled, i.e. not paused by admins or for having too few members.
+    enabled: bool,
+    /// Whether the game has been paused for having fewer than `min_members`, to be resumed once
+    /// there are enough.
+    short_of_members: bool,
+    /// The ongoing tournament, if any.
+    tournament: Option<Tournament>,
+    /// Mapping user ID and gun name to their number of consecutive survivals.
+    streaks: HashMap<(u64, String), usize>,
+    /// Users with a pending pull, who may still back out.
+    pending: HashSet<u64>,
+    /// Mapping user ID to the number of times they backed out.
+    chicken_outs: HashMap<u64, usize>,
+    /// Mapping user ID and gun name to the UNIX timestamp of their last play.
+    last_plays: HashMap<(u64, String), u64>,
+    /// Mapping user ID to their record of pulls.
+    user_stats: HashMap<u64, UserStats>,
+    /// UNIX timestamp of when the group was activated, starting the grace period.
+    activated_at: u64,
+}
+
+/// The revolvers of a group, or of one of its topics.
+#[derive(Clone, Debug)]
+struct Guns {
+    /// The Russian Roulette game, with the default gun.
+    roulette: Roulette,
+    /// Mapping name to additional guns, played separately.
+    others: HashMap<String, Roulette>,
+    /// Mapping gun name to until when it is being cleared after a jam.
+    jammed_until: HashMap<String, Instant>,
+}
+
+impl Guns {
+    /// Create the revolvers with the given default gun.
+    fn new(roulette: Roulette) -> Self {
+        Self {
+            roulette,
+            others: HashMap::new(),
+            jammed_until: HashMap::new(),
+        }
+    }
+
+    /// Start a fresh game of each gun under its config.
+    fn fresh(&self) -> Result<Self, ConfigError> {
+        let mut guns = Self::new(self.roulette.config().clone().start()?);
+        for (name, gun) in &self.others {
+            guns.others
+                .insert(name.clone(), gun.config().clone().start()?);
+        }
+        Ok(guns)
+    }
+
+    /// Get the named gun, or `None` if there's no such gun.
+    fn get(&self, name: &str) -> Option<&Roulette> {
+        if name == DEFAULT_GUN {
+            Some(&self.roulette)
+        } else {
+            self.others.get(name)
+        }
+    }
+
+    /// Get the named gun mutably, or `None` if there's no such gun.
+    fn get_mut(&mut self, name: &str) -> Option<&mut Roulette> {
+        if name == DEFAULT_GUN {
+            Some(&mut self.roulette)
+        } else {
+            self.others.get_mut(name)
+        }
+    }
+
+    /// Iterate over all guns mutably.
+    fn all_mut(&mut self) -> impl Iterator<Item = &mut Roulette> {
+        std::iter::once(&mut self.roulette).chain(self.others.values_mut())
+    }
+
+    /// Get the statistics of all guns combined.
+    fn stats(&self) -> Stats {
+        let mut stats = self.roulette.stats();
+        for gun in self.others.values() {
+            stats += gun.stats();
+        }
+        stats
+    }
+
+    /// Capture the loadings of the additional guns.
+    fn snapshots(&self) -> HashMap<String, RouletteState> {
+        self.others
+            .iter()
+            .map(|(name, gun)| (name.clone(), gun.snapshot()))
+            .collect()
+    }
+
+    /// Restore the loadings of the guns still configured, pushing the reasons why any were
+    /// discarded, prefixed with `context`, to `errors`.
+    fn restore(
+        &mut self,
+        roulette: Option<&RouletteState>,
+        others: &HashMap<String, RouletteState>,
+        context: &str,
+        errors: &mut Vec<String>,
+    ) {
+        let states = roulette
+            .map(|state| (DEFAULT_GUN, state))
+            .into_iter()
+            .chain(others.iter().map(|(name, state)| (name.as_str(), state)));
+        for (name, state) in states {
+            if let Some(gun) = self.get_mut(name)
+                && let Err(err) = gun.restore(state)
+            {
+                errors.push(format!("{context}{name}: {err}"));
+            }
+        }
+    }
+}
+
+/// Record of pulls by a user.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UserStats {
+    /// Display name of the user at their latest pull.
+    pub name: String,
+    /// Number of times the user got shot.
+    pub shot: u64,
+    /// Number of times the user survived.
+    pub survived: u64,
+}
+
+impl Group {
+    /// Create a new group with the given game, enabled by default.
+    pub fn new(roulette: Roulette) -> Self {
+        Self {
+            guns: Guns::new(roulette),
+            topics: HashMap::new(),
+            enabled: true,
+            short_of_members: false,
+            tournament: None,
+            streaks: HashMap::new(),
+            pending: HashSet::new(),
+            chicken_outs: HashMap::new(),
+            last_plays: HashMap::new(),
+            user_stats: HashMap::new(),
+            activated_at: 0,
+        }
+    }
+
+    /// Start a new group with a game of the default gun and of each named gun under the config.
+    pub fn start(config: &RouletteConfig, guns: &[String]) -> Result<Self, ConfigError> {
+        let mut group = Self::new(config.clone().start()?);
+        for name in guns {
+            group.add_gun(name.clone(), config.clone().start()?);
+        }
+        Ok(group)
+    }
+
+    /// Get the default gun played outside of topics, regardless of whether the game is enabled.
+    ///
+    /// All guns share its config.
+    pub fn roulette(&self) -> &Roulette {
+        &self.guns.roulette
+    }
+
+    /// Get the default gun played outside of topics mutably, regardless of whether the game is enabled.
+    pub fn roulette_mut(&mut self) -> &mut Roulette {
+        &mut self.guns.roulette
+    }
+
+    /// Get the default gun of the topic if the game is enabled, or `None` if it is paused.
+    pub fn playable(&mut self, topic: Option<i32>) -> Option<&mut Roulette> {
+        self.playable_gun(topic, DEFAULT_GUN)
+    }
+
+    /// Add a named gun, replacing the gun with the same name if any.
+    ///
+    /// Topics started afterwards get a fresh game of it too.
+    pub fn add_gun(&mut self, name: String, roulette: Roulette) {
+        if name == DEFAULT_GUN {
+            self.guns.roulette = roulette;
+        } else {
+            self.guns.others.insert(name, roulette);
+        }
+    }
+
+    /// Apply a new config to all guns of all topics, restarting only those whose loading no longer fits.
+    ///
+    /// Topics are dropped if `per_topic` is turned off.
+    pub fn reconfigure(&mut self, config: &RouletteConfig) -> Result<(), ConfigError> {
+        config.validate()?;
+        if !config.per_topic() {
+            self.topics.clear();
+        }
+        let topics = self.topics.values_mut();
+        for gun in std::iter::once(&mut self.guns)
+            .chain(topics)
+            .flat_map(Guns::all_mut)
+        {
+            gun.reconfigure(config.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Start the revolvers of the topic, if the group plays `per_topic` and they haven't been started yet.
+    pub fn open_topic(&mut self, topic: i32) -> Result<(), ConfigError> {
+        if !self.guns.roulette.config().per_topic() || self.topics.contains_key(&topic) {
+            return Ok(());
+        }
+        self.topics.insert(topic, self.guns.fresh()?);
+        Ok(())
+    }
+
+    /// Get the revolvers of the topic, or those played outside of topics if it has none of its own.
+    fn guns(&self, topic: Option<i32>) -> &Guns {
+        topic
+            .and_then(|topic| self.topics.get(&topic))
+            .unwrap_or(&self.guns)
+    }
+
+    /// Get the revolvers of the topic mutably, or those played outside of topics if it has none of its own.
+    fn guns_mut(&mut self, topic: Option<i32>) -> &mut Guns {
+        match topic.and_then(|topic| self.topics.get_mut(&topic)) {
+            Some(guns) => guns,
+            None => &mut self.guns,
+        }
+    }
+
+    /// Get the names of all guns, the default one first.
+    pub fn gun_names(&self) -> Vec<&str> {
+        let mut names: Vec<_> = self.guns.others.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names.insert(0, DEFAULT_GUN);
+        names
+    }
+
+    /// Whether there's a gun with the given name.
+    pub fn has_gun(&self, name: &str) -> bool {
+        self.guns.get(name).is_some()
+    }
+
+    /// Get the named gun of the topic regardless of whether the game is enabled, or `None` if there's no such gun.
+    pub fn gun(&self, topic: Option<i32>, name: &str) -> Option<&Roulette> {
+        self.guns(topic).get(name)
+    }
+
+    /// Get the named gun of the topic mutably regardless of whether the game is enabled, or `None` if there's no such gun.
+    pub fn gun_mut(&mut self, topic: Option<i32>, name: &str) -> Option<&mut Roulette> {
+        self.guns_mut(topic).get_mut(name)
+    }
+
+    /// Get the named gun of the topic if the game is enabled, or `None` if it is paused or there's no such gun.
+    pub fn playable_gun(&mut self, topic: Option<i32>, name: &str) -> Option<&mut Roulette> {
+        if !self.enabled {
+            return None;
+        }
+        self.gun_mut(topic, name)
+    }
+
+    /// Get the statistics of all guns of all topics combined.
+    pub fn stats(&self) -> Stats {
+        let mut stats = self.guns.stats();
+        for guns in self.topics.values() {
+            stats += guns.stats();
+        }
+        stats
+    }
+
+    /// Whether the game is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enable or disable the game, returning whether the state changed.
+    ///
+    /// Admins have the last word, so that a pause for too few members is no longer lifted by
+    /// [`Group::check_members`].
+    pub fn set_enabled(&mut self, enabled: bool) -> bool {
+        let changed = self.enabled != enabled;
+        self.enabled = enabled;
+        self.short_of_members = false;
+        changed
+    }
+
+    /// Pause the enabled game if the group has too few members, or resume it once there are
+    /// enough if it was paused so, returning whether the state changed.
+    pub fn check_members(&mut self, enough: bool) -> bool {
+        if self.enabled && !enough {
+            self.enabled = false;
+            self.short_of_members = true;
+            true
+        } else if self.short_of_members && enough {
+            self.enabled = true;
+            self.short_of_members = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get the ongoing tournament, if any.
+    pub fn tournament(&mut self) -> &mut Option<Tournament> {
+        &mut self.tournament
+    }
+
+    /// Record that the user survived a pull of the gun, returning whether they hit the jackpot streak, which then starts over.
+    pub fn record_survival(&mut self, user_id: u64, gun: &str) -> bool {
+        let target = self.roulette().config().jackpot_streak();
+        let streak = self.streaks.entry((user_id, gun.to_string())).or_default();
+        *streak += 1;
+        if target != 0 && *streak >= target {
+            *streak = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Record that the user got shot by the gun, resetting their streak with it.
+    pub fn record_shot(&mut self, user_id: u64, gun: &str) {
+        self.streaks.remove(&(user_id, gun.to_string()));
+    }
+
+    /// Record the outcome of a pull by the user, shot or survived, under their current display name.
+    pub fn record_outcome(&mut self, user_id: u64, name: &str, shot: bool) {
+        let stats = self.user_stats.entry(user_id).or_default();
+        name.clone_into(&mut stats.name);
+        if shot {
+            stats.shot += 1;
+        } else {
+            stats.survived += 1;
+        }
+    }
+
+    /// Get the records of pulls by each user.
+    pub fn user_stats(&self) -> &HashMap<u64, UserStats> {
+        &self.user_stats
+    }
+
+    /// Begin a pending pull for the user, returning `false` if they already have one.
+    pub fn begin_pull(&mut self, user_id: u64) -> bool {
+        self.pending.insert(user_id)
+    }
+
+    /// Finish the pending pull of the user, returning `false` if they have backed out.
+    pub fn finish_pull(&mut self, user_id: u64) -> bool {
+        self.pending.remove(&user_id)
+    }
+
+    /// Back out of the pending pull, returning the number of times the user has backed out, or `None` if there's no pending pull.
+    pub fn chicken_out(&mut self, user_id: u64) -> Option<usize> {
+        if !self.pending.remove(&user_id) {
+            return None;
+        }
+        let count = self.chicken_outs.entry(user_id).or_default();
+        *count += 1;
+        Some(*count)
+    }
+
+    /// Start clearing the jammed gun of the topic, if the jam cooldown is enabled.
+    pub fn jam(&mut self, topic: Option<i32>, gun: &str) {
+        let cooldown = self.roulette().config().jam_cooldown_secs();
+        if cooldown > 0 {
+            let until = Instant::now() + Duration::from_secs(cooldown);
+            self.guns_mut(topic)
+                .jammed_until
+                .insert(gun.to_string(), until);
+        }
+    }
+
+    /// Get the time left for clearing the jammed gun of the topic, or `None` if it can be fired.
+    pub fn jam_cooldown(&self, topic: Option<i32>, gun: &str) -> Option<Duration> {
+        let left = self
+            .guns(topic)
+            .jammed_until
+            .get(gun)?
+            .saturating_duration_since(Instant::now());
+        (!left.is_zero()).then_some(left)
+    }
+
+    /// Clear the jammed gun of the topic immediately.
+    pub fn clear_jam(&mut self, topic: Option<i32>, gun: &str) {
+        self.guns_mut(topic).jammed_until.remove(gun);
+    }
+
+    /// Get the seconds left before the user can play the gun again at the UNIX timestamp `now`, or `None` if they can play.
+    pub fn play_cooldown(&self, user_id: u64, gun: &str, now: u64) -> Option<u64> {
+        let cooldown = self.roulette().config().cooldown_secs();
+        let last = self.last_plays.get(&(user_id, gun.to_string()))?;
+        let left = (last + cooldown).saturating_sub(now);
+        (left > 0).then_some(left)
+    }
+
+    /// Record that the user played the gun at the UNIX timestamp `now`, forgetting plays whose cooldown has passed.
+    pub fn record_play(&mut self, user_id: u64, gun: &str, now: u64) {
+        let cooldown = self.roulette().config().cooldown_secs();
+        if cooldown == 0 {
+            return;
+        }
+        self.last_plays.retain(|_, last| *last + cooldown > now);
+        self.last_plays.insert((user_id, gun.to_string()), now);
+    }
+
+    /// Record that the group was activated at the UNIX timestamp `now`, starting the grace period.
+    pub fn activate(&mut self, now: u64) {
+        self.activated_at = now;
+    }
+
+    /// Whether bullets are only warning shots at the UNIX timestamp `now`, since the group was activated less than `grace_period_secs` ago.
+    pub fn in_grace_period(&self, now: u64) -> bool {
+        let grace = self.roulette().config().grace_period_secs();
+        now < self.activated_at.saturating_add(grace)
+    }
+
+    /// Forget the per-user stats and play cooldowns, e.g. on the daily reset.
+    pub fn reset_users(&mut self) {
+        self.user_stats.clear();
+        self.last_plays.clear();
+    }
+
+    /// Capture the persistent state of the group.
+    pub fn state(&self) -> GroupState {
+        GroupState {
+            // Checked again on startup, which skips groups with too few members
+            enabled: self.enabled || self.short_of_members,
+            roulette: Some(self.guns.roulette.snapshot()),
+            guns: self.guns.snapshots(),
+            topics: self
+                .topics
+                .iter()
+                .map(|(&topic, guns)| {
+                    let state = TopicState {
+                        roulette: guns.roulette.snapshot(),
+                        guns: guns.snapshots(),
+                    };
+                    (topic, state)
+                })
+                .collect(),
+        }
+    }
+
+    /// Restore the persistent state of the group.
+    ///
+    /// If a persisted revolver doesn't match the config, the freshly loaded one is kept and the reason is returned, after restoring the rest.
+    /// Persisted guns no longer configured are dropped, as are topics without `per_topic`.
+    pub fn restore(&mut self, state: &GroupState) -> Result<(), String> {
+        self.enabled = state.enabled;
+        let mut errors = Vec::new();
+        self.guns
+            .restore(state.roulette.as_ref(), &state.guns, "", &mut errors);
+        for (&topic, topic_state) in &state.topics {
+            if let Err(err) = self.open_topic(topic) {
+                errors.push(format!("topic {topic}: {err}"));
+            }
+            if let Some(guns) = self.topics.get_mut(&topic) {
+                let context = format!("topic {topic} ");
+                guns.restore(
+                    Some(&topic_state.roulette),
+                    &topic_state.guns,
+                    &context,
+                    &mut errors,
+                );
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            errors.sort_unstable();
+            Err(errors.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RouletteConfig;
+
+    #[test]
+    fn test_enabled() {
+        let mut group = Group::new(RouletteConfig::default().start().unwrap());
+        assert!(group.playable(None).is_some());
+
+        // Rejected while disabled
+        assert!(group.set_enabled(false));
+        assert!(!group.set_enabled(false));
+        assert!(group.playable(None).is_none());
+
+        // Resumes after enabled
+        assert!(group.set_enabled(true));
+        assert!(group.playable(None).is_some());
+    }
+
+    #[test]
+    fn test_check_members() {
+        let mut group = Group::new(RouletteConfig::default().start().unwrap());
+        assert!(!group.check_members(true));
+
+        // Paused below the minimum, but persisted as enabled
+        assert!(group.check_members(false));
+        assert!(!group.check_members(false));
+        assert!(group.playable(None).is_none());
+        assert!(group.state().enabled);
+
+        // Resumed once there are enough members
+        assert!(group.check_members(true));
+        assert!(group.playable(None).is_some());
+
+        // Never resuming a pause by admins
+        group.set_enabled(false);
+        assert!(!group.check_members(true));
+        assert!(!group.is_enabled());
+        group.set_enabled(true);
+        group.check_members(false);
+        group.set_enabled(false);
+        assert!(!group.check_members(true));
+        assert!(!group.state().enabled);
+    }
+
+    #[test]
+    fn test_guns() {
+        let config = RouletteConfig {
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        let mut group = Group::new(config.clone().start().unwrap());
+        group.add_gun("left".to_string(), config.start().unwrap());
+        assert_eq!(group.gun_names(), [DEFAULT_GUN, "left"]);
+        assert!(group.has_gun("left"));
+        assert!(group.playable_gun(None, "right").is_none());
+
+        // Guns are fired separately
+        group.playable_gun(None, "left").unwrap().fire();
+        assert_eq!(group.playable_gun(None, "left").unwrap().position(), 1);
+        assert_eq!(group.playable_gun(None, DEFAULT_GUN).unwrap().position(), 0);
+
+        // Jams, streaks and cooldowns are kept separately too
+        let config = RouletteConfig {
+            jackpot_streak: 2,
+            jam_cooldown_secs: 60,
+            cooldown_secs: 60,
+            ..Default::default()
+        };
+        group.reconfigure(&config).unwrap();
+        group.jam(None, "left");
+        assert!(group.jam_cooldown(None, "left").is_some());
+        assert_eq!(group.jam_cooldown(None, DEFAULT_GUN), None);
+        assert!(!group.record_survival(1, "left"));
+        assert!(!group.record_survival(1, DEFAULT_GUN));
+        assert!(group.record_survival(1, "left"));
+        group.record_play(1, "left", 1000);
+        assert_eq!(group.play_cooldown(1, "left", 1000), Some(60));
+        assert_eq!(group.play_cooldown(1, DEFAULT_GUN, 1000), None);
+
+        // Paused altogether
+        group.set_enabled(false);
+        assert!(group.playable_gun(None, "left").is_none());
+    }
+
+    #[test]
+    fn test_state() {
+        let config = RouletteConfig {
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        let guns = ["left".to_string(), "right".to_string()];
+        let mut group = Group::start(&config, &guns).unwrap();
+        group.playable_gun(None, "left").unwrap().fire();
+        group.set_enabled(false);
+        let state = group.state();
+
+        // Every gun is restored
+        let mut restored = Group::start(&config, &guns).unwrap();
+        restored.restore(&state).unwrap();
+        assert!(!restored.is_enabled());
+        for name in group.gun_names() {
+            assert_eq!(restored.gun(None, name), group.gun(None, name), "{name}");
+        }
+
+        // Guns no longer configured are dropped, and mismatching ones are reported
+        let smaller = RouletteConfig {
+            chambers: 3,
+            ..config.clone()
+        };
+        let mut restored = Group::start(&smaller, &guns[..1]).unwrap();
+        let fresh = restored.gun(None, "left").unwrap().clone();
+        assert_eq!(
+            restored.restore(&state),
+            Err(format!(
+                "default: {err}, left: {err}",
+                err = restored
+                    .roulette()
+                    .clone()
+                    .restore(&state.guns["left"])
+                    .unwrap_err()
+            ))
+        );
+        assert_eq!(restored.gun(None, "left"), Some(&fresh));
+        assert_eq!(restored.gun_names(), [DEFAULT_GUN, "left"]);
+    }
+
+    #[test]
+    fn test_topics() {
+        let config = RouletteConfig {
+            jam_probability: 0.0,
+            per_topic: true,
+            cooldown_secs: 60,
+            ..Default::default()
+        };
+        let guns = ["left".to_string()];
+        let mut group = Group::start(&config, &guns).unwrap();
+        group.open_topic(5).unwrap();
+
+        // Each topic fires its own revolvers, the stats of which are summed
+        group.playable_gun(Some(5), "left").unwrap().fire();
+        assert_eq!(group.playable_gun(Some(5), "left").unwrap().position(), 1);
+        assert_eq!(group.playable_gun(None, "left").unwrap().position(), 0);
+        assert_eq!(group.stats().fires, 1);
+        // Topics not started play outside of topics
+        assert_eq!(group.gun(Some(6), "left"), group.gun(None, "left"));
+
+        // Cooldowns are per gun across topics, and pausing pauses every topic
+        group.record_play(1, "left", 1000);
+        assert_eq!(group.play_cooldown(1, "left", 1000), Some(60));
+        group.set_enabled(false);
+        assert!(group.playable(Some(5)).is_none());
+
+        // Topics are persisted
+        let state = group.state();
+        let mut restored = Group::start(&config, &guns).unwrap();
+        restored.restore(&state).unwrap();
+        assert_eq!(restored.gun(Some(5), "left"), group.gun(Some(5), "left"));
+
+        // And dropped once the group no longer plays per topic
+        let config = RouletteConfig {
+            per_topic: false,
+            ..config
+        };
+        group.reconfigure(&config).unwrap();
+        assert_eq!(group.stats().fires, 0);
+        assert!(group.state().topics.is_empty());
+    }
+
+    #[test]
+    fn test_jackpot() {
+        let config = RouletteConfig {
+            jackpot_streak: 3,
+            ..Default::default()
+        };
+        let mut group = Group::new(config.start().unwrap());
+
+        // Hit exactly the streak
+        assert!(!group.record_survival(1, DEFAULT_GUN));
+        assert!(!group.record_survival(1, DEFAULT_GUN));
+        assert!(group.record_survival(1, DEFAULT_GUN));
+        // Starts over after the jackpot
+        assert!(!group.record_survival(1, DEFAULT_GUN));
+
+        // A bullet resets progress
+        assert!(!group.record_survival(2, DEFAULT_GUN));
+        assert!(!group.record_survival(2, DEFAULT_GUN));
+        group.record_shot(2, DEFAULT_GUN);
+        assert!(!group.record_survival(2, DEFAULT_GUN));
+        assert!(!group.record_survival(2, DEFAULT_GUN));
+        assert!(group.record_survival(2, DEFAULT_GUN));
+    }
+
+    #[test]
+    fn test_chicken_out() {
+        let mut group = Group::new(RouletteConfig::default().start().unwrap());
+        assert_eq!(group.chicken_out(1), None);
+
+        // Back out of a pending pull
+        assert!(group.begin_pull(1));
+        assert!(!group.begin_pull(1));
+        assert_eq!(group.chicken_out(1), Some(1));
+        assert!(!group.finish_pull(1));
+
+        // Pull without backing out
+        assert!(group.begin_pull(1));
+        assert!(group.finish_pull(1));
+        assert_eq!(group.chicken_out(1), None);
+    }
+
+    #[test]
+    fn test_jam_cooldown() {
+        // Disabled by default
+        let mut group = Group::new(RouletteConfig::default().start().unwrap());
+        group.jam(None, DEFAULT_GUN);
+        assert_eq!(group.jam_cooldown(None, DEFAULT_GUN), None);
+
+        let config = RouletteConfig {
+            jam_cooldown_secs: 60,
+            ..Default::default()
+        };
+        let mut group = Group::new(config.start().unwrap());
+        group.jam(None, DEFAULT_GUN);
+        assert!(group.jam_cooldown(None, DEFAULT_GUN).unwrap() <= Duration::from_secs(60));
+        group.clear_jam(None, DEFAULT_GUN);
+        assert_eq!(group.jam_cooldown(None, DEFAULT_GUN), None);
+    }
+
+    #[test]
+    fn test_play_cooldown() {
+        // Disabled by default
+        let mut group = Group::new(RouletteConfig::default().start().unwrap());
+        group.record_play(1, DEFAULT_GUN, 1000);
+        assert_eq!(group.play_cooldown(1, DEFAULT_GUN, 1000), None);
+
+        let config = RouletteConfig {
+            cooldown_secs: 60,
+            ..Default::default()
+        };
+        let mut group = Group::new(config.start().unwrap());
+        assert_eq!(group.play_cooldown(1, DEFAULT_GUN, 1000), None);
+        group.record_play(1, DEFAULT_GUN, 1000);
+        assert_eq!(group.play_cooldown(1, DEFAULT_GUN, 1000), Some(60));
+        assert_eq!(group.play_cooldown(1, DEFAULT_GUN, 1059), Some(1));
+        assert_eq!(group.play_cooldown(1, DEFAULT_GUN, 1060), None);
+        // Other users are unaffected
+        assert_eq!(group.play_cooldown(2, DEFAULT_GUN, 1000), None);
+
+        // Expired plays are forgotten
+        group.record_play(2, DEFAULT_GUN, 1060);
+        assert!(!group.last_plays.contains_key(&(1, DEFAULT_GUN.to_string())));
+
+        // Cleared by the daily reset, along with the stats
+        group.record_outcome(2, "Ferris", true);
+        group.reset_users();
+        assert_eq!(group.play_cooldown(2, DEFAULT_GUN, 1060), None);
+        assert!(group.user_stats().is_empty());
+    }
+
+    #[test]
+    fn test_grace_period() {
+        // Disabled by default
+        let mut group = Group::new(RouletteConfig::default().start().unwrap());
+        group.activate(1000);
+        assert!(!group.in_grace_period(1000));
+
+        let config = RouletteConfig {
+            grace_period_secs: 300,
+            ..Default::default()
+        };
+        let mut group = Group::new(config.start().unwrap());
+        group.activate(1000);
+        assert!(group.in_grace_period(1000));
+        assert!(group.in_grace_period(1299));
+        assert!(!group.in_grace_period(1300));
+    }
+}
@@ -1,18 +1,50 @@
 mod commands;
+pub mod config;
 mod constants;
+pub mod digest;
+mod group;
+pub mod locale;
+pub mod logging;
+pub mod members;
+pub mod metrics;
+#[cfg(test)]
+mod mock;
+pub mod ratelimit;
+pub mod reset;
+pub mod server;
+pub mod state;
+pub mod tournament;
+pub mod update;
 
-pub use commands::Commands;
+pub use commands::{Api, CommandOutcome, Commands, Triggers};
+pub use config::ConfigFormat;
 use frankenstein::{
-    client_reqwest::Bot, methods::{DeleteMyCommandsParams, SetMyCommandsParams, SetMyDefaultAdministratorRightsParams}, types::BotCommandScope, AsyncTelegramApi, Error
+    AsyncTelegramApi, Error,
+    client_reqwest::Bot,
+    methods::{DeleteMyCommandsParams, SetMyCommandsParams, SetMyDefaultAdministratorRightsParams},
+    types::BotCommandScope,
+};
+pub use group::{Group, UserStats};
+use jiff::tz::TimeZone;
+use locale::{Locale, MessageKey};
+use rand::{Rng, SeedableRng, seq::index::sample};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use state::RouletteState;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    net::SocketAddr,
+    ops::AddAssign,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
 };
-use rand::{Rng, seq::index::sample};
-use serde::Deserialize;
-use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Configuration for the bot.
 #[derive(Deserialize)]
 pub struct Config {
-    /// The token for the bot.
+    /// The token for the bot, overridden by the `ROULETTE_BOT_TOKEN` environment variable if set.
+    #[serde(default)]
     pub token: String,
     /// The configuration for the Russian Roulette game.
     #[serde(default)]
@@ -20,10 +52,88 @@ pub struct Config {
     /// The override configuration for groups.
     #[serde(default)]
     pub groups: Vec<GroupConfig>,
+    /// Delete informational replies of the bot after this many seconds.
+    #[serde(default)]
+    pub reply_ttl_secs: Option<u64>,
+    /// Leave configured chats where the bot cannot function.
+    #[serde(default)]
+    pub leave_unusable_chats: bool,
+    /// Treat messages edited into commands as commands, instead of ignoring them.
+    #[serde(default)]
+    pub process_edits: bool,
+    /// Reply to messages in private chats, telling that the bot only works in groups, empty to disable.
+    #[serde(default = "constants::private_reply")]
+    pub private_reply: String,
+    /// Path to the file persisting group states across restarts.
+    #[serde(default)]
+    pub state_file: Option<PathBuf>,
+    /// Seconds between flushes of the state file.
+    #[serde(default = "constants::state_flush_secs", alias = "stats_flush_secs")]
+    pub state_flush_secs: u64,
+    /// Address to serve the HTTP endpoints (e.g. health check) on, disabled if absent.
+    #[serde(default)]
+    pub http_addr: Option<SocketAddr>,
+    /// Address to export metrics in the Prometheus text format on, disabled if absent.
+    #[serde(default)]
+    pub metrics_addr: Option<SocketAddr>,
+    /// Maximum seconds since the last successful poll, for the bot to be considered healthy.
+    #[serde(default = "constants::health_threshold_secs")]
+    pub health_threshold_secs: u64,
+    /// How to receive updates from Telegram.
+    #[serde(default)]
+    pub mode: Mode,
+    /// Public URL that Telegram pushes updates to, required in webhook mode.
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Path to receive updates at on `http_addr`, in webhook mode.
+    #[serde(default = "constants::webhook_path")]
+    pub webhook_path: String,
+    /// Secret token that Telegram sends along with each update, for rejecting forged requests.
+    #[serde(default)]
+    pub webhook_secret: Option<String>,
+    /// Hour (UTC) to reset per-user stats and play cooldowns at daily, disabled if absent.
+    #[serde(default)]
+    pub stats_reset_hour: Option<u8>,
+    /// Mapping canonical command names (e.g. `roulette`) to the trigger words to use instead.
+    #[serde(default)]
+    pub command_triggers: HashMap<String, String>,
+    /// Only log restrictions instead of carrying them out, for trying the bot out in a test group.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Day of the week to post a digest of the week's activity to each group on, disabled if absent.
+    #[serde(default)]
+    pub digest_weekday: Option<digest::Weekday>,
+    /// Hour (UTC) to post the weekly digest at.
+    #[serde(default)]
+    pub digest_hour: u8,
+}
+
+impl Config {
+    /// Check the bot-level settings the bot cannot start with, beyond what parsing ensures.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.stats_reset_hour.is_some_and(|hour| hour > 23) {
+            return Err("`stats_reset_hour` must be between 0 and 23".to_string());
+        }
+        if self.digest_hour > 23 {
+            return Err("`digest_hour` must be between 0 and 23".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// How to receive updates from Telegram.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    /// Long polling with `getUpdates`.
+    #[default]
+    Polling,
+    /// Updates pushed by Telegram to the HTTP server.
+    Webhook,
 }
 
 /// Configuration for the Russian Roulette game.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct RouletteConfig {
     /// Number of chambers in the revolver.
     #[serde(default = "constants::chambers")]
@@ -31,51 +141,269 @@ pub struct RouletteConfig {
     /// Number of bullets in the revolver.
     #[serde(default = "constants::bullets")]
     bullets: usize,
-    /// Probability of the gun getting jammed.
+    /// Bullets added on each reload, starting over from `bullets` once beyond `max_bullets`, disabled if absent or `0`.
+    #[serde(default)]
+    bullet_ramp: Option<usize>,
+    /// Maximum number of bullets ramped up to with `bullet_ramp`, all chambers if absent, and ignored without a ramp.
+    #[serde(default)]
+    max_bullets: Option<usize>,
+    /// Probability of the gun getting jammed, where `0` disables jams and `1` always jams.
     #[serde(default = "constants::jam_probability")]
     jam_probability: f64,
+    /// Increase of the jam probability per chamber fired since the last reload.
+    #[serde(default = "constants::jam_escalation")]
+    jam_escalation: f64,
+    /// Probability of a bullet misfiring, sparing the player while still consuming the chamber.
+    #[serde(default = "constants::misfire_probability")]
+    misfire_probability: f64,
     /// Minimum time to mute in seconds.
     #[serde(default = "constants::min_mute_time")]
     min_mute_time: u32,
     /// Maximum time to mute in seconds.
     #[serde(default = "constants::max_mute_time")]
     max_mute_time: u32,
+    /// Whether each bullet hit in a loading mutes longer than the previous one, from `min_mute_time` up to `max_mute_time`, instead of a random time in between.
+    #[serde(default = "constants::escalating_mute")]
+    escalating_mute: bool,
+    /// Minimum number of members for the group to be playable.
+    #[serde(default = "constants::min_members")]
+    min_members: u32,
+    /// Time window in seconds for joining a tournament.
+    #[serde(default = "constants::tournament_join_secs")]
+    tournament_join_secs: u64,
+    /// Number of consecutive survivals for a jackpot, `0` meaning disabled.
+    #[serde(default = "constants::jackpot_streak")]
+    jackpot_streak: usize,
+    /// Whether to announce reloads in a standalone message, instead of in the reply to the player.
+    #[serde(default = "constants::announce_reload")]
+    announce_reload: bool,
+    /// Whether to append the reload tip to the reply that empties the gun.
+    #[serde(default = "constants::show_reload_tip")]
+    show_reload_tip: bool,
+    /// Whether to reload the gun once no bullets are left, instead of waiting for an admin to `/reload` it.
+    #[serde(default = "constants::auto_reload")]
+    auto_reload: bool,
+    /// Emoji reactions to the command message for each outcome, disabled if absent.
+    #[serde(default)]
+    reactions: Option<Reactions>,
+    /// Language of the replies.
+    #[serde(default)]
+    language: Locale,
+    /// Templates of the replies, overriding the bundled ones of the language.
+    #[serde(default)]
+    messages: Messages,
+    /// Time window in seconds for backing out of a pull with `/chicken`, `0` meaning disabled.
+    #[serde(default = "constants::chicken_window_secs")]
+    chicken_window_secs: u64,
+    /// Seconds for clearing a jammed gun, during which it cannot be fired, `0` meaning disabled.
+    #[serde(default = "constants::jam_cooldown_secs")]
+    jam_cooldown_secs: u64,
+    /// Whether a jam skips the current chamber, instead of retrying it on the next pull.
+    #[serde(default = "constants::jam_advances")]
+    jam_advances: bool,
+    /// Seconds a user has to wait between two plays, `0` meaning disabled.
+    #[serde(default = "constants::cooldown_secs", alias = "cooldown_seconds")]
+    cooldown_secs: u64,
+    /// Seconds after the group is activated during which bullets are only warning shots that mute nobody, `0` meaning disabled.
+    #[serde(
+        default = "constants::grace_period_secs",
+        alias = "grace_period_seconds"
+    )]
+    grace_period_secs: u64,
+    /// Whether admins other than the owner may play.
+    #[serde(default = "constants::admins_can_play")]
+    admins_can_play: bool,
+    /// Whether admins can dump the loading of the revolver with `/debug`, for checking game balance.
+    #[serde(default = "constants::debug_enabled")]
+    debug_enabled: bool,
+    /// Whether each topic of a forum supergroup has its own game.
+    #[serde(default = "constants::per_topic")]
+    per_topic: bool,
+    /// Whether players surviving a loading win once only bullets are left in it.
+    #[serde(default = "constants::survivors_win")]
+    survivors_win: bool,
+    /// Total pulls in the group to celebrate reaching, none to disable.
+    #[serde(default)]
+    milestones: Vec<u64>,
+    /// How bullets are decided on each pull.
+    #[serde(default = "constants::mode")]
+    mode: FireMode,
+    /// What `/peek` reveals.
+    #[serde(default = "constants::peek_mode")]
+    peek_mode: PeekMode,
+    /// Probability of peeking discharging the gun instead, wasting the current chamber without revealing anything.
+    #[serde(default = "constants::peek_fire_probability")]
+    peek_fire_probability: f64,
+    /// Whether `/peek` also tells the current jam probability, if any.
+    #[serde(default = "constants::peek_shows_jam")]
+    peek_shows_jam: bool,
+    /// Timezone for displaying times, as an IANA name like `Asia/Shanghai`.
+    #[serde(default = "constants::timezone")]
+    timezone: String,
+    /// Seed for the random number generator, making the game reproducible, random if absent.
+    #[serde(default)]
+    seed: Option<u64>,
+}
+
+/// Emoji reactions to the command message for each outcome.
+///
+/// Telegram only accepts a [fixed set of emoji](https://core.telegram.org/bots/api#reactiontypeemoji) as reactions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reactions {
+    /// Reaction when the player got shot.
+    #[serde(default = "constants::bullet_reaction")]
+    pub bullet: String,
+    /// Reaction when the player survived, including by a misfire.
+    #[serde(default = "constants::empty_reaction")]
+    pub empty: String,
+    /// Reaction when the gun got jammed.
+    #[serde(default = "constants::jammed_reaction")]
+    pub jammed: String,
+}
+
+impl Reactions {
+    /// Get the reaction for the given outcome, if any.
+    pub fn get(&self, result: FireResult) -> Option<&str> {
+        match result {
+            FireResult::Bullet { .. } => Some(&self.bullet),
+            FireResult::Empty | FireResult::Misfire | FireResult::WarningShot => Some(&self.empty),
+            FireResult::Jammed => Some(&self.jammed),
+            FireResult::NoBullets => None,
+        }
+    }
+}
+
+/// How bullets are decided on each pull.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum FireMode {
+    /// The revolver is loaded with a fixed number of bullets, and reloaded once all are fired.
+    #[default]
+    Fixed,
+    /// Each pull independently hits with the given probability, so the revolver never runs out.
+    Probabilistic {
+        /// Probability of each pull hitting.
+        hit_probability: f64,
+    },
+}
+
+impl fmt::Display for FireMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fixed => write!(f, "fixed"),
+            Self::Probabilistic { hit_probability } => {
+                write!(
+                    f,
+                    "probabilistic ({:.1}% per pull)",
+                    hit_probability * 100.0
+                )
+            }
+        }
+    }
+}
+
+/// What `/peek` reveals.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PeekMode {
+    /// The counts of filled and left chambers.
+    #[default]
+    Counts,
+    /// Only whether the next chamber is loaded.
+    Next,
+}
+
+impl fmt::Display for PeekMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Counts => write!(f, "counts"),
+            Self::Next => write!(f, "next"),
+        }
+    }
+}
+
+/// Templates of the replies mapped by key, where placeholders like `{name}` are substituted.
+///
+/// Absent templates fall back to the bundled ones of the [`Locale`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Messages(HashMap<MessageKey, String>);
+
+impl Messages {
+    /// Get the template for the given key, if customized.
+    pub fn get(&self, key: MessageKey) -> Option<&str> {
+        self.0.get(&key).map(String::as_str)
+    }
+
+    /// Get these templates with those of `overlay` taking precedence, key by key.
+    pub fn overlaid(&self, overlay: &Self) -> Self {
+        let mut messages = self.clone();
+        messages.0.extend(overlay.0.clone());
+        messages
+    }
 }
 
 impl RouletteConfig {
-    /// Starts a new game of Russian Roulette.
-    pub fn start(self) -> Result<Roulette, &'static str> {
-        // Sanity check
-        if self.chambers <= 0 {
-            return Err("Number of chambers must be greater than 0");
+    /// Start building a config from the defaults.
+    pub fn builder() -> RouletteConfigBuilder {
+        RouletteConfigBuilder::default()
+    }
+
+    /// Starts a new game of Russian Roulette, with the configured seed or a random one.
+    pub fn start(self) -> Result<Roulette, ConfigError> {
+        let seed = self.seed.unwrap_or_else(|| rand::rng().random());
+        Roulette::from_seed(self, seed)
+    }
+
+    /// Sanity check of the configuration.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.chambers == 0 {
+            return Err(ConfigError::ZeroChambers);
         }
-        if self.bullets <= 0 {
-            return Err("Number of bullets must be greater than 0");
+        if self.bullets == 0 {
+            return Err(ConfigError::ZeroBullets);
         }
         if self.bullets > self.chambers {
-            return Err("Number of bullets must be less than or equal to number of chambers");
+            return Err(ConfigError::BulletsExceedChambers);
+        }
+        if self
+            .max_bullets
+            .is_some_and(|max| max < self.bullets || max > self.chambers)
+        {
+            return Err(ConfigError::InvalidMaxBullets);
+        }
+        // Also rejects NaN
+        if !(0.0..=1.0).contains(&self.jam_probability) {
+            return Err(ConfigError::InvalidJamProbability);
+        }
+        if !(0.0..=1.0).contains(&self.misfire_probability) {
+            return Err(ConfigError::InvalidMisfireProbability);
+        }
+        if !(0.0..=1.0).contains(&self.peek_fire_probability) {
+            return Err(ConfigError::InvalidPeekFireProbability);
+        }
+        if self.jam_escalation.is_nan() || self.jam_escalation < 0.0 {
+            return Err(ConfigError::NegativeJamEscalation);
+        }
+        if let FireMode::Probabilistic { hit_probability } = self.mode
+            && !(0.0..=1.0).contains(&hit_probability)
+        {
+            return Err(ConfigError::InvalidHitProbability);
         }
         if self.min_mute_time < 30 {
-            return Err("Minimum mute time must be greater than or equal to 30 seconds");
+            return Err(ConfigError::MuteTimeTooShort);
         }
-        if self.max_mute_time > 3600 {
-            // FIXME: 365 days
-            return Err("Maximum mute time must be less than or equal to 3600 seconds");
+        if self.max_mute_time > constants::MAX_MUTE_TIME {
+            return Err(ConfigError::MuteTimeTooLong);
         }
         if self.min_mute_time > self.max_mute_time {
-            return Err("Minimum mute time must be less than or equal to maximum mute time");
+            return Err(ConfigError::MinExceedsMax);
+        }
+        if TimeZone::get(&self.timezone).is_err() {
+            return Err(ConfigError::InvalidTimezone);
         }
 
-        // Initialize the contents of the chambers
-        let contents = vec![false; self.chambers];
-        let mut roulette = Roulette {
-            config: self,
-            contents,
-            position: 0,
-        };
-        roulette.reload();
-
-        Ok(roulette)
+        Ok(())
     }
 
     /// Get the number of bullets and chambers.
@@ -83,19 +411,380 @@ impl RouletteConfig {
         (self.bullets, self.chambers)
     }
 
-    /// Generate a random mute time and the time until which the user will be muted.
-    pub fn random_mute_until(&self) -> (u64, u64) {
+    /// Get the number of chambers in the revolver.
+    pub fn chambers(&self) -> usize {
+        self.chambers
+    }
+
+    /// Get the number of bullets in the revolver.
+    pub fn bullets(&self) -> usize {
+        self.bullets
+    }
+
+    /// Get the number of bullets added on each reload, if ramping up.
+    pub fn bullet_ramp(&self) -> Option<usize> {
+        self.bullet_ramp.filter(|&ramp| ramp > 0)
+    }
+
+    /// Get the maximum number of bullets ramped up to, defaulting to all chambers.
+    pub fn max_bullets(&self) -> usize {
+        self.max_bullets.unwrap_or(self.chambers)
+    }
+
+    /// Get the number of bullets of the loading after one with `current` bullets, ramping up
+    /// with `bullet_ramp` and starting over from `bullets` once beyond `max_bullets`.
+    ///
+    /// A count under `bullets`, e.g. of a loading under a previous config, also starts over.
+    pub fn next_bullets(&self, current: usize) -> usize {
+        let Some(ramp) = self.bullet_ramp() else {
+            return self.bullets;
+        };
+        match current.checked_add(ramp) {
+            Some(next) if current >= self.bullets && next <= self.max_bullets() => next,
+            _ => self.bullets,
+        }
+    }
+
+    /// Whether a loading may have the given number of bullets, which varies with `bullet_ramp`.
+    fn allows_bullets(&self, count: usize) -> bool {
+        if self.bullet_ramp().is_some() {
+            (self.bullets..=self.max_bullets()).contains(&count)
+        } else {
+            count == self.bullets
+        }
+    }
+
+    /// Get the base probability of the gun getting jammed, before any escalation.
+    pub fn jam_probability(&self) -> f64 {
+        self.jam_probability
+    }
+
+    /// Get the increase of the jam probability per chamber fired since the last reload.
+    pub fn jam_escalation(&self) -> f64 {
+        self.jam_escalation
+    }
+
+    /// Get the probability of a bullet misfiring.
+    pub fn misfire_probability(&self) -> f64 {
+        self.misfire_probability
+    }
+
+    /// Get the minimum time to mute in seconds.
+    pub fn min_mute_time(&self) -> u32 {
+        self.min_mute_time
+    }
+
+    /// Get the maximum time to mute in seconds.
+    pub fn max_mute_time(&self) -> u32 {
+        self.max_mute_time
+    }
+
+    /// Whether mute times escalate with each bullet hit in a loading.
+    pub fn escalating_mute(&self) -> bool {
+        self.escalating_mute
+    }
+
+    /// Get the minimum number of members for the group to be playable.
+    pub fn min_members(&self) -> u32 {
+        self.min_members
+    }
+
+    /// Whether a group with the given number of members is large enough to be playable.
+    pub fn has_enough_members(&self, count: u32) -> bool {
+        count >= self.min_members
+    }
+
+    /// Get the time window in seconds for joining a tournament.
+    pub fn tournament_join_secs(&self) -> u64 {
+        self.tournament_join_secs
+    }
+
+    /// Get the number of consecutive survivals for a jackpot, `0` meaning disabled.
+    pub fn jackpot_streak(&self) -> usize {
+        self.jackpot_streak
+    }
+
+    /// Whether to announce reloads in a standalone message, instead of in the reply to the player.
+    pub fn announce_reload(&self) -> bool {
+        self.announce_reload
+    }
+
+    /// Get whether to append the reload tip to the reply that empties the gun.
+    pub fn show_reload_tip(&self) -> bool {
+        self.show_reload_tip
+    }
+
+    /// Whether to reload the gun once no bullets are left, instead of waiting for an admin to `/reload` it.
+    pub fn auto_reload(&self) -> bool {
+        self.auto_reload
+    }
+
+    /// Get the time window in seconds for backing out of a pull, `0` meaning disabled.
+    pub fn chicken_window_secs(&self) -> u64 {
+        self.chicken_window_secs
+    }
+
+    /// Get the seconds for clearing a jammed gun, `0` meaning disabled.
+    pub fn jam_cooldown_secs(&self) -> u64 {
+        self.jam_cooldown_secs
+    }
+
+    /// Whether a jam skips the current chamber, instead of retrying it on the next pull.
+    pub fn jam_advances(&self) -> bool {
+        self.jam_advances
+    }
+
+    /// Get the seconds a user has to wait between two plays, `0` meaning disabled.
+    pub fn cooldown_secs(&self) -> u64 {
+        self.cooldown_secs
+    }
+
+    /// Get the seconds after activation during which bullets mute nobody.
+    pub fn grace_period_secs(&self) -> u64 {
+        self.grace_period_secs
+    }
+
+    /// Whether admins other than the owner may play.
+    pub fn admins_can_play(&self) -> bool {
+        self.admins_can_play
+    }
+
+    /// Get whether admins can dump the loading of the revolver with `/debug`.
+    pub fn debug_enabled(&self) -> bool {
+        self.debug_enabled
+    }
+
+    /// Whether each topic of a forum supergroup has its own game.
+    pub fn per_topic(&self) -> bool {
+        self.per_topic
+    }
+
+    /// Whether players surviving a loading win once only bullets are left in it.
+    pub fn survivors_win(&self) -> bool {
+        self.survivors_win
+    }
+
+    /// Get the milestones of total pulls to celebrate, in any order.
+    pub fn milestones(&self) -> &[u64] {
+        &self.milestones
+    }
+
+    /// Get the mode of deciding bullets.
+    pub fn mode(&self) -> FireMode {
+        self.mode
+    }
+
+    /// What `/peek` reveals.
+    pub fn peek_mode(&self) -> PeekMode {
+        self.peek_mode
+    }
+
+    /// Get the probability of peeking discharging the gun.
+    pub fn peek_fire_probability(&self) -> f64 {
+        self.peek_fire_probability
+    }
+
+    /// Whether `/peek` tells the current jam probability.
+    pub fn peek_shows_jam(&self) -> bool {
+        self.peek_shows_jam
+    }
+
+    /// Get the timezone for displaying times.
+    pub fn timezone(&self) -> &str {
+        &self.timezone
+    }
+
+    /// Get the language of the replies.
+    pub fn language(&self) -> Locale {
+        self.language
+    }
+
+    /// Get the template of the reply for the given key, customized or bundled with the language.
+    pub fn message(&self, key: MessageKey) -> &str {
+        self.messages
+            .get(key)
+            .unwrap_or_else(|| self.language.get(key))
+    }
+
+    /// Get the emoji reactions for each outcome, if enabled.
+    pub fn reactions(&self) -> Option<&Reactions> {
+        self.reactions.as_ref()
+    }
+
+    /// Get the configured seed for the random number generator, if any.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Generate a random mute time with the given generator, and the time until which the user will be muted.
+    pub fn random_mute_until(&self, rng: &mut impl Rng) -> (u64, u64) {
+        self.random_mute_until_at(rng, SystemTime::now())
+    }
+
+    /// Generate a random mute time, and the time until which the user will be muted counting from `now`.
+    fn random_mute_until_at(&self, rng: &mut impl Rng, now: SystemTime) -> (u64, u64) {
         // Generate a random mute time between min and max
-        let mut rng = rand::rng();
         let duration: u64 = rng
             .random_range(self.min_mute_time..=self.max_mute_time)
             .into();
-        // Convert to seconds and add to current time
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("Time went backwards")
-            .as_secs();
-        (duration, now + duration)
+        (duration, mute_until_at(duration, now))
+    }
+
+    /// Get the mute time of the bullet hit after `hits` others in the same loading of `bullets` with `escalating_mute`.
+    ///
+    /// The time is interpolated from `min_mute_time` for the first bullet to `max_mute_time` for the last one, and stays there for any further hits, like in the probabilistic mode.
+    pub fn escalated_mute_time(&self, hits: usize, bullets: usize) -> u64 {
+        let steps = bullets.saturating_sub(1).max(1) as u64;
+        let step = (hits as u64).min(steps);
+        let (min, max) = (u64::from(self.min_mute_time), u64::from(self.max_mute_time));
+        min + (max - min) * step / steps
+    }
+}
+
+/// Get the time until which the user will be muted for `duration` seconds counting from `now`.
+///
+/// The end is pushed back by [`MUTE_MARGIN_SECS`](constants::MUTE_MARGIN_SECS), since Telegram
+/// counts the remaining time from when the restriction arrives, which could drop it under
+/// the 30 seconds that it requires to not ban permanently.
+fn mute_until_at(duration: u64, now: SystemTime) -> u64 {
+    // Convert to seconds and add to current time, treating a clock before the epoch as the epoch
+    let now = now
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    now.saturating_add(duration)
+        .saturating_add(constants::MUTE_MARGIN_SECS)
+}
+
+impl fmt::Display for RouletteConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let enabled = |value: u64, unit: &str| {
+            if value == 0 {
+                "disabled".to_string()
+            } else {
+                format!("{value}{unit}")
+            }
+        };
+        writeln!(f, "Chambers: {}", self.chambers)?;
+        writeln!(f, "Bullets: {}", self.bullets)?;
+        if let Some(ramp) = self.bullet_ramp() {
+            writeln!(
+                f,
+                "Bullet ramp: +{ramp} per reload, up to {}",
+                self.max_bullets()
+            )?;
+        }
+        writeln!(f, "Jam probability: {:.1}%", self.jam_probability * 100.0)?;
+        writeln!(f, "Jam escalation: {:.1}%", self.jam_escalation * 100.0)?;
+        writeln!(
+            f,
+            "Misfire probability: {:.1}%",
+            self.misfire_probability * 100.0
+        )?;
+        writeln!(
+            f,
+            "Mute time: {}s - {}s",
+            self.min_mute_time, self.max_mute_time
+        )?;
+        writeln!(
+            f,
+            "Escalating mute: {}",
+            if self.escalating_mute { "yes" } else { "no" }
+        )?;
+        writeln!(
+            f,
+            "Minimum members: {}",
+            enabled(self.min_members.into(), "")
+        )?;
+        writeln!(f, "Tournament join window: {}s", self.tournament_join_secs)?;
+        writeln!(
+            f,
+            "Jackpot streak: {}",
+            enabled(self.jackpot_streak as u64, "")
+        )?;
+        writeln!(
+            f,
+            "Announce reloads: {}",
+            if self.announce_reload { "yes" } else { "no" }
+        )?;
+        writeln!(
+            f,
+            "Reload tip: {}",
+            if self.show_reload_tip { "yes" } else { "no" }
+        )?;
+        writeln!(
+            f,
+            "Auto reload: {}",
+            if self.auto_reload { "yes" } else { "no" }
+        )?;
+        writeln!(
+            f,
+            "Chicken window: {}",
+            enabled(self.chicken_window_secs, "s")
+        )?;
+        writeln!(f, "Jam cooldown: {}", enabled(self.jam_cooldown_secs, "s"))?;
+        writeln!(
+            f,
+            "Jam advances: {}",
+            if self.jam_advances { "yes" } else { "no" }
+        )?;
+        writeln!(f, "Play cooldown: {}", enabled(self.cooldown_secs, "s"))?;
+        writeln!(f, "Grace period: {}", enabled(self.grace_period_secs, "s"))?;
+        writeln!(
+            f,
+            "Admins can play: {}",
+            if self.admins_can_play { "yes" } else { "no" }
+        )?;
+        writeln!(
+            f,
+            "Debug: {}",
+            if self.debug_enabled {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        )?;
+        writeln!(
+            f,
+            "Per topic: {}",
+            if self.per_topic { "yes" } else { "no" }
+        )?;
+        writeln!(
+            f,
+            "Survivors win: {}",
+            if self.survivors_win { "yes" } else { "no" }
+        )?;
+        if self.milestones.is_empty() {
+            writeln!(f, "Milestones: disabled")?;
+        } else {
+            let milestones: Vec<_> = self.milestones.iter().map(u64::to_string).collect();
+            writeln!(f, "Milestones: {}", milestones.join(", "))?;
+        }
+        writeln!(f, "Mode: {}", self.mode)?;
+        writeln!(f, "Peek mode: {}", self.peek_mode)?;
+        writeln!(
+            f,
+            "Peek fire probability: {:.1}%",
+            self.peek_fire_probability * 100.0
+        )?;
+        writeln!(
+            f,
+            "Peek shows jam: {}",
+            if self.peek_shows_jam { "yes" } else { "no" }
+        )?;
+        writeln!(f, "Timezone: {}", self.timezone)?;
+        writeln!(f, "Language: {}", self.language)?;
+        match self.seed {
+            Some(seed) => writeln!(f, "Seed: {seed}")?,
+            None => writeln!(f, "Seed: random")?,
+        }
+        match &self.reactions {
+            Some(reactions) => write!(
+                f,
+                "Reactions: {} / {} / {}",
+                reactions.bullet, reactions.empty, reactions.jammed
+            ),
+            None => write!(f, "Reactions: disabled"),
+        }
     }
 }
 
@@ -104,15 +793,165 @@ impl Default for RouletteConfig {
         Self {
             chambers: constants::chambers(),
             bullets: constants::bullets(),
+            bullet_ramp: None,
+            max_bullets: None,
             jam_probability: constants::jam_probability(),
+            jam_escalation: constants::jam_escalation(),
+            misfire_probability: constants::misfire_probability(),
             min_mute_time: constants::min_mute_time(),
             max_mute_time: constants::max_mute_time(),
+            escalating_mute: constants::escalating_mute(),
+            min_members: constants::min_members(),
+            tournament_join_secs: constants::tournament_join_secs(),
+            jackpot_streak: constants::jackpot_streak(),
+            announce_reload: constants::announce_reload(),
+            show_reload_tip: constants::show_reload_tip(),
+            auto_reload: constants::auto_reload(),
+            reactions: None,
+            language: Locale::default(),
+            messages: Messages::default(),
+            chicken_window_secs: constants::chicken_window_secs(),
+            jam_cooldown_secs: constants::jam_cooldown_secs(),
+            jam_advances: constants::jam_advances(),
+            cooldown_secs: constants::cooldown_secs(),
+            grace_period_secs: constants::grace_period_secs(),
+            admins_can_play: constants::admins_can_play(),
+            debug_enabled: constants::debug_enabled(),
+            per_topic: constants::per_topic(),
+            survivors_win: constants::survivors_win(),
+            milestones: Vec::new(),
+            mode: constants::mode(),
+            peek_mode: constants::peek_mode(),
+            peek_fire_probability: constants::peek_fire_probability(),
+            peek_shows_jam: constants::peek_shows_jam(),
+            timezone: constants::timezone(),
+            seed: None,
         }
     }
 }
 
+/// Builder of a [`RouletteConfig`], with the defaults for options not set.
+#[derive(Clone, Debug, Default)]
+pub struct RouletteConfigBuilder {
+    /// The config built so far.
+    config: RouletteConfig,
+}
+
+impl RouletteConfigBuilder {
+    /// Set the number of chambers in the revolver.
+    pub fn chambers(mut self, chambers: usize) -> Self {
+        self.config.chambers = chambers;
+        self
+    }
+
+    /// Set the number of bullets in the revolver.
+    pub fn bullets(mut self, bullets: usize) -> Self {
+        self.config.bullets = bullets;
+        self
+    }
+
+    /// Set the probability of the gun getting jammed.
+    pub fn jam_probability(mut self, jam_probability: f64) -> Self {
+        self.config.jam_probability = jam_probability;
+        self
+    }
+
+    /// Set the minimum time to mute in seconds.
+    pub fn min_mute_time(mut self, min_mute_time: u32) -> Self {
+        self.config.min_mute_time = min_mute_time;
+        self
+    }
+
+    /// Set the maximum time to mute in seconds.
+    pub fn max_mute_time(mut self, max_mute_time: u32) -> Self {
+        self.config.max_mute_time = max_mute_time;
+        self
+    }
+
+    /// Build the config, validating it like [`RouletteConfig::start`] does.
+    pub fn build(self) -> Result<RouletteConfig, ConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+/// Reason why a [`RouletteConfig`] is invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// There are no chambers.
+    ZeroChambers,
+    /// There are no bullets.
+    ZeroBullets,
+    /// There are more bullets than chambers.
+    BulletsExceedChambers,
+    /// The maximum number of ramped bullets is less than the bullets or more than the chambers.
+    InvalidMaxBullets,
+    /// The jam probability is not between 0 and 1.
+    InvalidJamProbability,
+    /// The misfire probability is not between 0 and 1.
+    InvalidMisfireProbability,
+    /// The probability of peeking discharging the gun is not between 0 and 1.
+    InvalidPeekFireProbability,
+    /// The jam escalation is negative or not a number.
+    NegativeJamEscalation,
+    /// The hit probability of the probabilistic mode is not between 0 and 1.
+    InvalidHitProbability,
+    /// The minimum mute time is shorter than 30 seconds.
+    MuteTimeTooShort,
+    /// The maximum mute time is longer than 365 days.
+    MuteTimeTooLong,
+    /// The minimum mute time is longer than the maximum.
+    MinExceedsMax,
+    /// The timezone is not a valid IANA timezone name.
+    InvalidTimezone,
+    /// A named gun is called like the default gun, or by a number, which would be taken as the shots.
+    ReservedGunName,
+    /// Two named guns share the same name.
+    DuplicateGunName,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            Self::ZeroChambers => "Number of chambers must be greater than 0",
+            Self::ZeroBullets => "Number of bullets must be greater than 0",
+            Self::BulletsExceedChambers => {
+                "Number of bullets must be less than or equal to number of chambers"
+            }
+            Self::InvalidMaxBullets => {
+                "Maximum number of bullets must be between number of bullets and number of chambers"
+            }
+            Self::NegativeJamEscalation => "Jam escalation must be non-negative",
+            Self::InvalidHitProbability => "Hit probability must be between 0 and 1",
+            Self::InvalidJamProbability => "Jam probability must be between 0 and 1",
+            Self::InvalidMisfireProbability => "Misfire probability must be between 0 and 1",
+            Self::InvalidPeekFireProbability => "Peek fire probability must be between 0 and 1",
+            Self::MuteTimeTooShort => {
+                "Minimum mute time must be greater than or equal to 30 seconds"
+            }
+            Self::MuteTimeTooLong => {
+                "Maximum mute time must be less than or equal to 31536000 seconds (365 days)"
+            }
+            Self::MinExceedsMax => {
+                "Minimum mute time must be less than or equal to maximum mute time"
+            }
+            Self::InvalidTimezone => "Timezone must be a valid IANA timezone name",
+            Self::ReservedGunName => {
+                "Gun names must not be empty, \"default\" or a number of shots"
+            }
+            Self::DuplicateGunName => "Gun names must be unique",
+        };
+        f.write_str(reason)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
 /// A Russian Roulette game.
-#[derive(Clone, Debug)]
+///
+/// Serializes with the state of its random number generator, so that a deserialized game plays on identically. Deserializing validates the config and the loading, like [`Roulette::from_seed`] and [`Roulette::restore`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedRoulette")]
 pub struct Roulette {
     /// Configuration for the game.
     config: RouletteConfig,
@@ -120,136 +959,735 @@ pub struct Roulette {
     contents: Vec<bool>,
     /// The current chamber index.
     position: usize,
+    /// The seed of the current loading.
+    seed: u64,
+    /// The random number generator, seeded with `seed` on each loading.
+    rng: ChaCha8Rng,
+    /// Statistics of the game so far.
+    stats: Stats,
+    /// IDs and names of the players who survived the current loading, in order of their first pull.
+    survivors: Vec<(u64, String)>,
+    /// Number of bullets hit in the current loading, escalating the mute time with `escalating_mute`.
+    #[serde(default)]
+    bullets_hit_this_loading: usize,
+}
+
+/// A [`Roulette`] as deserialized, before checking that it is playable.
+#[derive(Deserialize)]
+struct UncheckedRoulette {
+    config: RouletteConfig,
+    contents: Vec<bool>,
+    position: usize,
+    seed: u64,
+    rng: ChaCha8Rng,
+    stats: Stats,
+    survivors: Vec<(u64, String)>,
+    #[serde(default)]
+    bullets_hit_this_loading: usize,
+}
+
+impl TryFrom<UncheckedRoulette> for Roulette {
+    type Error = String;
+
+    fn try_from(unchecked: UncheckedRoulette) -> Result<Self, Self::Error> {
+        unchecked.config.validate().map_err(|e| e.to_string())?;
+        check_loading(&unchecked.config, &unchecked.contents, unchecked.position)?;
+        Ok(Self {
+            config: unchecked.config,
+            contents: unchecked.contents,
+            position: unchecked.position,
+            seed: unchecked.seed,
+            rng: unchecked.rng,
+            stats: unchecked.stats,
+            survivors: unchecked.survivors,
+            bullets_hit_this_loading: unchecked.bullets_hit_this_loading,
+        })
+    }
+}
+
+/// Check that the loading of the chambers and the position in it match the config.
+fn check_loading(
+    config: &RouletteConfig,
+    contents: &[bool],
+    position: usize,
+) -> Result<(), &'static str> {
+    if contents.len() != config.chambers {
+        return Err("Number of chambers doesn't match the config");
+    }
+    if !config.allows_bullets(contents.iter().filter(|&&loaded| loaded).count()) {
+        return Err("Number of bullets doesn't match the config");
+    }
+    if position > contents.len() {
+        return Err("Position is out of the chambers");
+    }
+    Ok(())
 }
 
 impl Roulette {
-    /// Reload the revolver.
+    /// Starts a new game of Russian Roulette, whose first loading is determined by the given seed.
+    ///
+    /// Given the same config and seed, the loaded chambers and jams are identical, since [`ChaCha8Rng`] is reproducible across platforms and versions.
+    pub fn from_seed(config: RouletteConfig, seed: u64) -> Result<Self, ConfigError> {
+        config.validate()?;
+
+        // Initialize the contents of the chambers
+        let contents = vec![false; config.chambers];
+        let mut roulette = Self {
+            config,
+            contents,
+            position: 0,
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            stats: Stats::default(),
+            survivors: Vec::new(),
+            bullets_hit_this_loading: 0,
+        };
+        roulette.load(roulette.config.bullets);
+
+        Ok(roulette)
+    }
+
+    /// Reload the revolver, with a new seed derived from the current one.
+    ///
+    /// This is a full reset of the round: the position goes back to the first chamber and the bullets are reshuffled. With `bullet_ramp`, the new loading has more bullets than the last one, until starting over.
     pub fn reload(&mut self) {
+        self.stats.reloads += 1;
+        self.seed = self.rng.random();
+        self.rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let bullets = self.config.next_bullets(self.bullets());
+        self.load(bullets);
+    }
+
+    /// Load the revolver with the given number of bullets using the current random number generator.
+    fn load(&mut self, bullets: usize) {
         self.position = 0;
         self.contents.fill(false);
+        self.survivors.clear();
+        self.bullets_hit_this_loading = 0;
 
         // Randomly choose `bullets` chambers to be loaded with bullets.
-        let mut rng = rand::rng();
-        let selected = sample(&mut rng, self.contents.len(), self.config.bullets);
+        let selected = sample(&mut self.rng, self.contents.len(), bullets);
         for i in selected {
             self.contents[i] = true;
         }
     }
 
-    /// Get the number of bullets and chambers.
-    pub fn info(&self) -> (usize, usize) {
-        self.config.info()
+    /// Get the status of the current loading, as the number of shots fired, filled chambers left and chambers left.
+    ///
+    /// Unlike [`Roulette::peek`], this also tells how far into the loading the game is.
+    pub fn status(&self) -> (usize, usize, usize) {
+        let (filled, left) = self.peek();
+        (self.position, filled, left)
     }
 
-    /// Generate a random mute time and the time until which the user will be muted.
-    pub fn random_mute_until(&self) -> (u64, u64) {
-        self.config.random_mute_until()
+    /// Spin the cylinder, reshuffling the remaining bullets among all chambers and starting over from the first one.
+    pub fn spin(&mut self) {
+        let bullets = self.peek().0;
+        self.position = 0;
+        self.contents.fill(false);
+        for i in sample(&mut self.rng, self.contents.len(), bullets) {
+            self.contents[i] = true;
+        }
     }
 
-    /// Try to fire the current chamber.
+    /// Apply a new config, keeping the current loading and position if it still fits.
     ///
-    /// - If the chamber is loaded with a bullet, return `Some(true)`
-    /// - If the chamber is empty, return `Some(false)`
-    /// - If we have fired all filled chambers, return `None`
-    pub fn fire(&mut self) -> FireResult {
-        if self.peek().0 == 0 {
-            // No filled chambers left
-            return FireResult::NoBullets;
+    /// The revolver is reloaded only if the number of chambers or bullets, or their ramp, changes, since the current loading would contradict the config otherwise.
+    pub fn reconfigure(&mut self, config: RouletteConfig) -> Result<(), ConfigError> {
+        config.validate()?;
+        let reload = config.info() != self.config.info()
+            || (config.bullet_ramp(), config.max_bullets)
+                != (self.config.bullet_ramp(), self.config.max_bullets);
+        self.config = config;
+        if reload {
+            self.contents = vec![false; self.config.chambers];
+            self.reload();
         }
 
-        // Check if the gun is jammed
-        let jammed = rand::rng().random_bool(self.config.jam_probability);
-        if jammed {
-            return FireResult::Jammed;
+        Ok(())
+    }
+
+    /// Capture the loading of the revolver for persistence.
+    pub fn snapshot(&self) -> RouletteState {
+        RouletteState {
+            contents: self.contents.clone(),
+            position: self.position,
+            seed: self.seed,
+            word_pos: self.rng.get_word_pos(),
+            stats: self.stats,
+            bullets_hit: self.bullets_hit_this_loading,
         }
+    }
 
-        let result = self.contents[self.position];
-        self.position += 1;
+    /// Restore a persisted loading of the revolver, rejecting it if it doesn't match the config.
+    ///
+    /// The random number generator picks up where it left off, so the restored game plays on identically.
+    pub fn restore(&mut self, state: &RouletteState) -> Result<(), &'static str> {
+        check_loading(&self.config, &state.contents, state.position)?;
+        self.contents.clone_from(&state.contents);
+        self.position = state.position;
+        self.seed = state.seed;
+        self.rng = ChaCha8Rng::seed_from_u64(state.seed);
+        self.rng.set_word_pos(state.word_pos);
+        self.stats = state.stats;
+        self.bullets_hit_this_loading = state.bullets_hit;
 
-        if result {
-            FireResult::Bullet
-        } else {
-            FireResult::Empty
-        }
+        Ok(())
     }
 
-    /// Peek the left-over chambers, returning count of filled and left chambers.
-    pub fn peek(&self) -> (usize, usize) {
-        let filled = self
-            .contents[self.position..]
-            .iter()
-            .filter(|&&x| x)
-            .count();
-        let left = self.contents.len() - self.position;
+    /// Get the seed of the current loading, which can be replayed by [`Roulette::from_seed`], if not ramped up by `bullet_ramp`.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Get the current chamber index, i.e. number of chambers fired in the current loading.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Get the statistics of the game so far.
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Get the configuration of the game.
+    pub fn config(&self) -> &RouletteConfig {
+        &self.config
+    }
+
+    /// Get the number of bullets of the current loading and chambers.
+    pub fn info(&self) -> (usize, usize) {
+        (self.bullets(), self.config.chambers)
+    }
+
+    /// Get the number of chambers in the revolver.
+    pub fn chambers(&self) -> usize {
+        self.config.chambers
+    }
+
+    /// Get the number of bullets of the current loading, fired or not, which varies with `bullet_ramp`.
+    pub fn bullets(&self) -> usize {
+        self.contents.iter().filter(|&&loaded| loaded).count()
+    }
+
+    /// Get the minimum time to mute in seconds.
+    pub fn min_mute_time(&self) -> u32 {
+        self.config.min_mute_time
+    }
+
+    /// Get the maximum time to mute in seconds.
+    pub fn max_mute_time(&self) -> u32 {
+        self.config.max_mute_time
+    }
+
+    /// Generate a random mute time and the time until which the user will be muted.
+    pub fn random_mute_until(&mut self) -> (u64, u64) {
+        self.config.random_mute_until(&mut self.rng)
+    }
+
+    /// Try to fire the current chamber, rolling the mute time if it is loaded with a bullet.
+    ///
+    /// A jam leaves the position as is by default, so the same chamber is pulled next time. With `jam_advances`, the jammed chamber is skipped along with its contents instead. A misfire, unlike a jam, always uses up the chamber.
+    pub fn fire(&mut self) -> FireResult {
+        self.fire_at(SystemTime::now(), false)
+    }
+
+    /// Try to fire the current chamber, with `now` as the start of the mute, or firing a bullet as
+    /// a [`FireResult::WarningShot`] if `warning`.
+    fn fire_at(&mut self, now: SystemTime, warning: bool) -> FireResult {
+        if self.is_empty() {
+            // No filled chambers left
+            return FireResult::NoBullets;
+        }
+
+        // Check if the gun is jammed
+        self.stats.fires += 1;
+        let jammed = self.rng.random_bool(self.jam_probability());
+        if jammed {
+            self.stats.jams += 1;
+            if self.config.jam_advances && self.config.mode == FireMode::Fixed {
+                self.position += 1;
+            }
+            return FireResult::Jammed;
+        }
+
+        let result = match self.config.mode {
+            FireMode::Fixed => {
+                let loaded = self.contents[self.position];
+                self.position += 1;
+                loaded
+            }
+            FireMode::Probabilistic { hit_probability } => self.rng.random_bool(hit_probability),
+        };
+
+        if result && self.misfires() {
+            FireResult::Misfire
+        } else if result && warning {
+            // Wasting the bullet, but neither counted nor rolling a mute
+            FireResult::WarningShot
+        } else if result {
+            self.stats.bullets += 1;
+            let (duration, until) = if self.config.escalating_mute {
+                let hits = self.bullets_hit_this_loading;
+                let duration = self.config.escalated_mute_time(hits, self.bullets());
+                (duration, mute_until_at(duration, now))
+            } else {
+                self.config.random_mute_until_at(&mut self.rng, now)
+            };
+            self.bullets_hit_this_loading += 1;
+            self.stats.mute_secs += duration;
+            FireResult::Bullet { duration, until }
+        } else {
+            FireResult::Empty
+        }
+    }
+
+    /// Fire up to `shots` chambers in a row, at least one and at most the chambers left, stopping at the first result other than [`FireResult::Empty`].
+    ///
+    /// The mute time of a bullet is multiplied by the number of pulls, i.e. one more than the clicks preceding it, capped at the maximum Telegram permits.
+    pub fn fire_many(&mut self, shots: usize) -> Vec<FireResult> {
+        self.fire_many_at(shots, SystemTime::now(), false)
+    }
+
+    /// Fire like [`Roulette::fire_many`], but a bullet is only a [`FireResult::WarningShot`], muting
+    /// nobody, e.g. within the grace period of a group.
+    pub fn fire_warning_shots(&mut self, shots: usize) -> Vec<FireResult> {
+        self.fire_many_at(shots, SystemTime::now(), true)
+    }
+
+    /// Fire up to `shots` chambers in a row, with `now` as the start of the mute, firing bullets
+    /// as warning shots if `warning`.
+    fn fire_many_at(&mut self, shots: usize, now: SystemTime, warning: bool) -> Vec<FireResult> {
+        let shots = shots.clamp(1, self.peek().1.max(1));
+        let mut results = Vec::with_capacity(shots);
+        for _ in 0..shots {
+            let result = self.fire_at(now, warning);
+            results.push(result);
+            if result != FireResult::Empty {
+                break;
+            }
+        }
+        let pulls = results.len() as u64;
+        if let Some(FireResult::Bullet { duration, until }) = results.last_mut() {
+            let start = *until - *duration;
+            let rolled = *duration;
+            *duration = duration
+                .saturating_mul(pulls)
+                .min(u64::from(constants::MAX_MUTE_TIME));
+            self.stats.mute_secs += *duration - rolled;
+            *until = start + *duration;
+        }
+        results
+    }
+
+    /// Simulate the given number of pulls on a copy of the game, reloading whenever no bullets are left like the bot does.
+    ///
+    /// Note that with reloading after the last bullet, the hit rate per pull is higher than `bullets / chambers`, e.g. `2 / 7` for one bullet in six chambers.
+    pub fn simulate(&self, rounds: usize) -> SimulationStats {
+        let mut roulette = self.clone();
+        let mut stats = SimulationStats::default();
+        for _ in 0..rounds {
+            match roulette.fire() {
+                FireResult::Empty => stats.empty += 1,
+                FireResult::Bullet { .. } => stats.bullet += 1,
+                FireResult::Jammed => stats.jammed += 1,
+                FireResult::Misfire => stats.misfire += 1,
+                FireResult::WarningShot => unreachable!("Simulations fire no warning shots"),
+                FireResult::NoBullets => unreachable!("Reloaded once no bullets are left"),
+            }
+            if roulette.is_empty() {
+                roulette.reload();
+            }
+        }
+        stats
+    }
+
+    /// Get the probability of jamming on the next pull, escalating with each chamber fired since the last reload.
+    ///
+    /// The base probability is [`RouletteConfig::jam_probability`].
+    pub fn jam_probability(&self) -> f64 {
+        let escalation = self.config.jam_escalation * self.position as f64;
+        (self.config.jam_probability + escalation).min(1.0)
+    }
+
+    /// Roll whether a bullet misfires, drawing from the RNG only if misfires are enabled.
+    fn misfires(&mut self) -> bool {
+        self.config.misfire_probability > 0.0
+            && self.rng.random_bool(self.config.misfire_probability)
+    }
+
+    /// Get the probability that the next pull is a bullet, where a jam or a misfire counts as surviving, or `0` if no bullets are left.
+    pub fn odds(&self) -> f64 {
+        let hit = match self.config.mode {
+            FireMode::Fixed => {
+                let (filled, left) = self.peek();
+                if left == 0 {
+                    return 0.0;
+                }
+                filled as f64 / left as f64
+            }
+            FireMode::Probabilistic { hit_probability } => hit_probability,
+        };
+        hit * (1.0 - self.jam_probability()) * (1.0 - self.config.misfire_probability)
+    }
+
+    /// Whether all bullets have been fired, so that the revolver needs reloading.
+    ///
+    /// Never in the probabilistic mode.
+    pub fn is_empty(&self) -> bool {
+        match self.config.mode {
+            FireMode::Fixed => self.peek().0 == 0,
+            FireMode::Probabilistic { .. } => false,
+        }
+    }
+
+    /// Whether every empty chamber of the current loading has been fired, leaving only bullets.
+    ///
+    /// Never in the probabilistic mode.
+    pub fn only_bullets_left(&self) -> bool {
+        match self.config.mode {
+            FireMode::Fixed => {
+                let (filled, left) = self.peek();
+                filled > 0 && filled == left
+            }
+            FireMode::Probabilistic { .. } => false,
+        }
+    }
+
+    /// Record the result of a player in the current loading, who is a survivor unless shot.
+    pub fn record_player(&mut self, user_id: u64, name: &str, shot: bool) {
+        let index = self.survivors.iter().position(|(id, _)| *id == user_id);
+        match (index, shot) {
+            (Some(index), true) => {
+                self.survivors.remove(index);
+            }
+            (Some(index), false) => self.survivors[index].1 = name.to_string(),
+            (None, true) => {}
+            (None, false) => self.survivors.push((user_id, name.to_string())),
+        }
+    }
+
+    /// Get the IDs and names of the players who survived the current loading.
+    pub fn survivors(&self) -> &[(u64, String)] {
+        &self.survivors
+    }
+
+    /// Take the IDs and names of the players who survived the current loading, leaving none.
+    pub fn take_survivors(&mut self) -> Vec<(u64, String)> {
+        std::mem::take(&mut self.survivors)
+    }
+
+    /// Roll whether peeking discharges the gun with `peek_fire_probability`, firing the current
+    /// chamber into the air if so.
+    ///
+    /// The discharge counts as a pull in the stats, and a bullet in the chamber is wasted, hurting
+    /// nobody, thus not counted as one. Jams and misfires don't apply, and nothing is recorded for the peeker, e.g. for
+    /// `survivors_win`. In the probabilistic mode, there's no chamber to waste, so only the peek
+    /// is lost. The RNG is only drawn when the probability is positive.
+    pub fn discharge_on_peek(&mut self) -> bool {
+        let probability = self.config.peek_fire_probability;
+        if probability == 0.0 || self.is_empty() || !self.rng.random_bool(probability) {
+            return false;
+        }
+        self.stats.fires += 1;
+        if self.config.mode == FireMode::Fixed {
+            self.position += 1;
+        }
+        true
+    }
+
+    /// Peek whether the next chamber is loaded, which is `false` if no chambers are left.
+    ///
+    /// In the probabilistic mode, the chambers don't decide the pulls, so this is meaningless.
+    pub fn peek_next(&self) -> bool {
+        self.contents.get(self.position) == Some(&true)
+    }
+
+    /// Peek the left-over chambers, returning count of filled and left chambers.
+    ///
+    /// In the probabilistic mode, this is estimated as the expected number of hits in a full cylinder, rounded up.
+    pub fn peek(&self) -> (usize, usize) {
+        if let FireMode::Probabilistic { hit_probability } = self.config.mode {
+            let chambers = self.contents.len();
+            let filled = (hit_probability * chambers as f64).ceil() as usize;
+            return (filled, chambers);
+        }
+        let filled = self.contents[self.position..]
+            .iter()
+            .filter(|&&x| x)
+            .count();
+        let left = self.contents.len() - self.position;
         (filled, left)
     }
 }
 
 /// Result of firing the revolver.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum FireResult {
     /// The chamber was empty.
     Empty,
-    /// The chamber was loaded with a bullet.
-    Bullet,
+    /// The chamber was loaded with a bullet, muting the player for `duration` seconds until the UNIX timestamp `until`.
+    Bullet { duration: u64, until: u64 },
     /// The gun got jammed.
     Jammed,
+    /// The chamber was loaded with a bullet, which misfired and spared the player.
+    Misfire,
+    /// The chamber was loaded with a bullet, fired only as a warning shot that spared the player.
+    WarningShot,
     /// No more bullets left.
     NoBullets,
 }
 
+/// Statistics of a game, counted since it started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Stats {
+    /// Number of pulls, including jams.
+    pub fires: u64,
+    /// Number of bullets fired.
+    pub bullets: u64,
+    /// Number of jams.
+    pub jams: u64,
+    /// Number of reloads.
+    pub reloads: u64,
+    /// Total seconds of the mutes dealt by bullets, absent in state files of older versions.
+    #[serde(default)]
+    pub mute_secs: u64,
+}
+
+impl Stats {
+    /// Get the statistics counted since the `earlier` snapshot of the same game.
+    pub fn since(self, earlier: Self) -> Self {
+        Self {
+            fires: self.fires.saturating_sub(earlier.fires),
+            bullets: self.bullets.saturating_sub(earlier.bullets),
+            jams: self.jams.saturating_sub(earlier.jams),
+            reloads: self.reloads.saturating_sub(earlier.reloads),
+            mute_secs: self.mute_secs.saturating_sub(earlier.mute_secs),
+        }
+    }
+}
+
+impl AddAssign for Stats {
+    fn add_assign(&mut self, other: Self) {
+        self.fires += other.fires;
+        self.bullets += other.bullets;
+        self.jams += other.jams;
+        self.reloads += other.reloads;
+        self.mute_secs += other.mute_secs;
+    }
+}
+
+impl FireResult {
+    /// Whether the player got shot.
+    pub fn is_bullet(self) -> bool {
+        matches!(self, Self::Bullet { .. })
+    }
+}
+
+/// Outcomes of a simulation by [`Roulette::simulate`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SimulationStats {
+    /// Number of empty chambers fired.
+    pub empty: usize,
+    /// Number of bullets fired.
+    pub bullet: usize,
+    /// Number of jams.
+    pub jammed: usize,
+    /// Number of misfires.
+    pub misfire: usize,
+}
+
+impl SimulationStats {
+    /// Get the observed rate of being shot per pull, or `0` if there were no pulls.
+    pub fn hit_rate(&self) -> f64 {
+        let pulls = self.empty + self.bullet + self.jammed + self.misfire;
+        if pulls == 0 {
+            0.0
+        } else {
+            self.bullet as f64 / pulls as f64
+        }
+    }
+}
+
 /// Configuration for a group.
 #[derive(Debug, Deserialize)]
 pub struct GroupConfig {
     /// The ID of the group.
     pub id: i64,
+    /// Names of additional guns, each played separately with `/roulette <name>`.
+    #[serde(default)]
+    pub guns: Vec<String>,
+    /// Message to post once the game is live in the group, with `{bullets}` and `{chambers}`, none if absent.
+    #[serde(default)]
+    start_message: Option<String>,
     /// Override number of chambers in the revolver.
     chambers: Option<usize>,
     /// Override number of bullets in the revolver.
     bullets: Option<usize>,
+    /// Override bullets added on each reload, `0` disabling a ramp of the game config.
+    bullet_ramp: Option<usize>,
+    /// Override maximum number of bullets ramped up to.
+    max_bullets: Option<usize>,
     /// Override probability of the gun getting jammed.
     jam_probability: Option<f64>,
+    /// Override increase of the jam probability per chamber fired.
+    jam_escalation: Option<f64>,
+    /// Override probability of a bullet misfiring.
+    misfire_probability: Option<f64>,
     /// Override minimum time to mute in seconds.
     min_mute_time: Option<u32>,
     /// Override maximum time to mute in seconds.
     max_mute_time: Option<u32>,
+    /// Override whether mute times escalate.
+    escalating_mute: Option<bool>,
+    /// Override minimum number of members for the group to be playable.
+    min_members: Option<u32>,
+    /// Override time window in seconds for joining a tournament.
+    tournament_join_secs: Option<u64>,
+    /// Override number of consecutive survivals for a jackpot.
+    jackpot_streak: Option<usize>,
+    /// Override whether to announce reloads in a standalone message.
+    announce_reload: Option<bool>,
+    /// Override whether the reload tip is shown.
+    show_reload_tip: Option<bool>,
+    /// Override whether to reload the gun once no bullets are left.
+    auto_reload: Option<bool>,
+    /// Override emoji reactions for each outcome.
+    reactions: Option<Reactions>,
+    /// Override language of the replies.
+    language: Option<Locale>,
+    /// Override templates of the replies, key by key over those of the game config.
+    #[serde(default)]
+    messages: Messages,
+    /// Override time window in seconds for backing out of a pull.
+    chicken_window_secs: Option<u64>,
+    /// Override seconds for clearing a jammed gun.
+    jam_cooldown_secs: Option<u64>,
+    /// Override whether a jam skips the current chamber.
+    jam_advances: Option<bool>,
+    /// Override seconds a user has to wait between two plays.
+    #[serde(alias = "cooldown_seconds")]
+    cooldown_secs: Option<u64>,
+    /// Override seconds after activation during which bullets mute nobody.
+    #[serde(alias = "grace_period_seconds")]
+    grace_period_secs: Option<u64>,
+    /// Override whether admins other than the owner may play.
+    admins_can_play: Option<bool>,
+    /// Override whether `/debug` is enabled.
+    debug_enabled: Option<bool>,
+    /// Override whether each topic of a forum supergroup has its own game.
+    per_topic: Option<bool>,
+    /// Override whether players surviving a loading win once only bullets are left in it.
+    survivors_win: Option<bool>,
+    /// Override milestones of total pulls to celebrate.
+    milestones: Option<Vec<u64>>,
+    /// Override mode of deciding bullets.
+    mode: Option<FireMode>,
+    /// Override what `/peek` reveals.
+    peek_mode: Option<PeekMode>,
+    /// Override probability of peeking discharging the gun.
+    peek_fire_probability: Option<f64>,
+    /// Override whether `/peek` tells the current jam probability.
+    peek_shows_jam: Option<bool>,
+    /// Override timezone for displaying times.
+    timezone: Option<String>,
+    /// Override seed for the random number generator.
+    seed: Option<u64>,
 }
 
 impl GroupConfig {
-    /// Resolves to a [`RouletteConfig`].
-    pub fn resolve(&self, default: &RouletteConfig) -> RouletteConfig {
-        let Self {
-            chambers,
-            bullets,
-            jam_probability,
-            min_mute_time,
-            max_mute_time,
-            ..
-        } = self;
-        let (chambers, bullets, jam_probability, min_mute_time, max_mute_time) = (
-            chambers.unwrap_or(default.chambers),
-            bullets.unwrap_or(default.bullets),
-            jam_probability.unwrap_or(default.jam_probability),
-            min_mute_time.unwrap_or(default.min_mute_time),
-            max_mute_time.unwrap_or(default.max_mute_time),
-        );
-        RouletteConfig {
-            chambers,
-            bullets,
-            jam_probability,
-            min_mute_time,
-            max_mute_time,
+    /// Resolves to a [`RouletteConfig`], checking that the result is valid.
+    pub fn resolve(&self, default: &RouletteConfig) -> Result<RouletteConfig, ConfigError> {
+        let resolved = RouletteConfig {
+            chambers: self.chambers.unwrap_or(default.chambers),
+            bullets: self.bullets.unwrap_or(default.bullets),
+            bullet_ramp: self.bullet_ramp.or(default.bullet_ramp),
+            max_bullets: self.max_bullets.or(default.max_bullets),
+            jam_probability: self.jam_probability.unwrap_or(default.jam_probability),
+            jam_escalation: self.jam_escalation.unwrap_or(default.jam_escalation),
+            misfire_probability: self
+                .misfire_probability
+                .unwrap_or(default.misfire_probability),
+            min_mute_time: self.min_mute_time.unwrap_or(default.min_mute_time),
+            max_mute_time: self.max_mute_time.unwrap_or(default.max_mute_time),
+            escalating_mute: self.escalating_mute.unwrap_or(default.escalating_mute),
+            min_members: self.min_members.unwrap_or(default.min_members),
+            tournament_join_secs: self
+                .tournament_join_secs
+                .unwrap_or(default.tournament_join_secs),
+            jackpot_streak: self.jackpot_streak.unwrap_or(default.jackpot_streak),
+            announce_reload: self.announce_reload.unwrap_or(default.announce_reload),
+            show_reload_tip: self.show_reload_tip.unwrap_or(default.show_reload_tip),
+            auto_reload: self.auto_reload.unwrap_or(default.auto_reload),
+            reactions: self.reactions.clone().or_else(|| default.reactions.clone()),
+            language: self.language.unwrap_or(default.language),
+            messages: default.messages.overlaid(&self.messages),
+            chicken_window_secs: self
+                .chicken_window_secs
+                .unwrap_or(default.chicken_window_secs),
+            jam_cooldown_secs: self.jam_cooldown_secs.unwrap_or(default.jam_cooldown_secs),
+            jam_advances: self.jam_advances.unwrap_or(default.jam_advances),
+            cooldown_secs: self.cooldown_secs.unwrap_or(default.cooldown_secs),
+            grace_period_secs: self.grace_period_secs.unwrap_or(default.grace_period_secs),
+            admins_can_play: self.admins_can_play.unwrap_or(default.admins_can_play),
+            debug_enabled: self.debug_enabled.unwrap_or(default.debug_enabled),
+            per_topic: self.per_topic.unwrap_or(default.per_topic),
+            survivors_win: self.survivors_win.unwrap_or(default.survivors_win),
+            milestones: self
+                .milestones
+                .clone()
+                .unwrap_or_else(|| default.milestones.clone()),
+            mode: self.mode.unwrap_or(default.mode),
+            peek_mode: self.peek_mode.unwrap_or(default.peek_mode),
+            peek_fire_probability: self
+                .peek_fire_probability
+                .unwrap_or(default.peek_fire_probability),
+            peek_shows_jam: self.peek_shows_jam.unwrap_or(default.peek_shows_jam),
+            timezone: self
+                .timezone
+                .clone()
+                .unwrap_or_else(|| default.timezone.clone()),
+            seed: self.seed.or(default.seed),
+        };
+        resolved.validate()?;
+        let mut names = HashSet::new();
+        for name in &self.guns {
+            if name.is_empty() || name == group::DEFAULT_GUN || name.parse::<usize>().is_ok() {
+                return Err(ConfigError::ReservedGunName);
+            }
+            if !names.insert(name) {
+                return Err(ConfigError::DuplicateGunName);
+            }
         }
+
+        Ok(resolved)
+    }
+
+    /// Render the message to post once the game is live with the resolved config, if any.
+    pub fn start_message(&self, resolved: &RouletteConfig) -> Option<String> {
+        let template = self.start_message.as_deref()?;
+        let (bullets, chambers) = resolved.info();
+        Some(commands::render(
+            template,
+            &[
+                ("bullets", &bullets.to_string()),
+                ("chambers", &chambers.to_string()),
+            ],
+        ))
     }
 }
 
-/// Set commands and default admin rights for the bot.
-pub async fn init_commands_and_rights(bot: &Bot) -> Result<(), Error> {
+/// Set commands under the triggers and default admin rights for the bot, listing `/debug` only if `debug` is enabled.
+pub async fn init_commands_and_rights(
+    bot: &Bot,
+    triggers: &Triggers,
+    debug: bool,
+) -> Result<(), Error> {
     let delete_param = DeleteMyCommandsParams::builder().build();
     bot.delete_my_commands(&delete_param).await?;
 
     let commands_param = SetMyCommandsParams::builder()
-        .commands(Commands::list())
+        .commands(triggers.list(debug))
         .scope(BotCommandScope::AllGroupChats)
         .build();
     bot.set_my_commands(&commands_param).await?;
@@ -272,19 +1710,58 @@ mod tests {
         let config = RouletteConfig {
             chambers: 3,
             bullets: 1,
+            bullet_ramp: None,
+            max_bullets: None,
             jam_probability: 0.0, // For testing purposes
+            jam_escalation: 0.0,
+            misfire_probability: 0.0,
             min_mute_time: 60,
             max_mute_time: 600,
+            escalating_mute: false,
+            min_members: 0,
+            tournament_join_secs: 60,
+            jackpot_streak: 0,
+            announce_reload: false,
+            show_reload_tip: true,
+            auto_reload: true,
+            reactions: None,
+            language: Locale::En,
+            messages: Messages::default(),
+            chicken_window_secs: 0,
+            jam_cooldown_secs: 0,
+            jam_advances: false,
+            cooldown_secs: 0,
+            grace_period_secs: 0,
+            admins_can_play: false,
+            debug_enabled: false,
+            per_topic: false,
+            survivors_win: false,
+            milestones: Vec::new(),
+            mode: FireMode::Fixed,
+            peek_mode: PeekMode::Counts,
+            peek_fire_probability: 0.0,
+            peek_shows_jam: false,
+            timezone: "UTC".to_string(),
+            seed: None,
         };
         // let mut roulette = config.start().unwrap();
         let mut roulette = Roulette {
             config,
             contents: vec![false, true, false],
             position: 0,
+            seed: 0,
+            rng: ChaCha8Rng::seed_from_u64(0),
+            stats: Stats::default(),
+            survivors: Vec::new(),
+            bullets_hit_this_loading: 0,
         };
 
         assert_eq!(roulette.fire(), FireResult::Empty);
-        assert_eq!(roulette.fire(), FireResult::Bullet);
+        let FireResult::Bullet { duration, until } = roulette.fire_at(UNIX_EPOCH, false) else {
+            panic!("Second chamber should be loaded");
+        };
+        assert!((60..=600).contains(&duration));
+        assert_eq!(until, duration + constants::MUTE_MARGIN_SECS);
         assert_eq!(roulette.fire(), FireResult::NoBullets);
         assert_eq!(roulette.fire(), FireResult::NoBullets);
     }
@@ -301,4 +1778,921 @@ mod tests {
         assert_eq!(roulette.peek().0, 2);
         assert_eq!(roulette.position, 0);
     }
+
+    #[test]
+    fn test_reload_mid_round() {
+        let config = RouletteConfig {
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        let mut roulette = config.start().unwrap();
+        while !roulette.fire().is_bullet() {}
+        assert_eq!(roulette.peek().0, 1);
+
+        roulette.reload();
+        assert_eq!(roulette.position, 0);
+        assert_eq!(roulette.peek(), (2, 6));
+        assert_eq!(
+            roulette.contents.iter().filter(|&&loaded| loaded).count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_reconfigure() {
+        let mut roulette = RouletteConfig::default().start().unwrap();
+        roulette.fire();
+        roulette.fire();
+        let snapshot = roulette.snapshot();
+
+        // Same chambers and bullets, so the loading and position are kept
+        let tweaked = RouletteConfig {
+            jam_probability: 0.5,
+            min_mute_time: 120,
+            ..Default::default()
+        };
+        roulette.reconfigure(tweaked).unwrap();
+        assert_eq!(roulette.config().min_mute_time(), 120);
+        assert_eq!(roulette.snapshot(), snapshot);
+
+        // Invalid configs are rejected, leaving the game as is
+        let invalid = RouletteConfig {
+            bullets: 0,
+            ..Default::default()
+        };
+        assert_eq!(roulette.reconfigure(invalid), Err(ConfigError::ZeroBullets));
+        assert_eq!(roulette.config().min_mute_time(), 120);
+
+        // More chambers, so the revolver is restarted
+        let bigger = RouletteConfig {
+            chambers: 8,
+            ..Default::default()
+        };
+        roulette.reconfigure(bigger).unwrap();
+        assert_eq!(roulette.position(), 0);
+        assert_eq!(roulette.peek(), (2, 8));
+    }
+
+    #[test]
+    fn test_escalating_mute() {
+        let config = RouletteConfig {
+            chambers: 6,
+            bullets: 3,
+            jam_probability: 0.0,
+            min_mute_time: 60,
+            max_mute_time: 600,
+            escalating_mute: true,
+            ..Default::default()
+        };
+        let mut roulette = Roulette::from_seed(config, 0).unwrap();
+        let durations = |roulette: &mut Roulette| {
+            let mut durations = Vec::new();
+            loop {
+                match roulette.fire_at(UNIX_EPOCH, false) {
+                    FireResult::Bullet { duration, until } => {
+                        assert_eq!(until, duration + constants::MUTE_MARGIN_SECS);
+                        durations.push(duration);
+                    }
+                    FireResult::NoBullets => break durations,
+                    _ => {}
+                }
+            }
+        };
+        assert_eq!(durations(&mut roulette), [60, 330, 600]);
+        // Starts over after a reload
+        roulette.reload();
+        assert_eq!(durations(&mut roulette), [60, 330, 600]);
+
+        // Stays at the maximum past the last bullet, and a single bullet mutes for the minimum
+        let config = roulette.config();
+        assert_eq!(config.escalated_mute_time(5, 3), 600);
+        assert_eq!(config.escalated_mute_time(0, 1), 60);
+        assert_eq!(config.escalated_mute_time(1, 1), 600);
+    }
+
+    #[test]
+    fn test_fire_many() {
+        let config = RouletteConfig {
+            chambers: 6,
+            bullets: 1,
+            jam_probability: 0.0,
+            min_mute_time: 60,
+            max_mute_time: 60,
+            ..Default::default()
+        };
+        // Find a loading with the bullet in the third chamber
+        let mut roulette = (0..)
+            .map(|seed| Roulette::from_seed(config.clone(), seed).unwrap())
+            .find(|roulette| roulette.contents[2])
+            .unwrap();
+
+        // Stops early on the bullet, muting three times as long
+        let results = roulette.clone().fire_many_at(5, UNIX_EPOCH, false);
+        let bullet = FireResult::Bullet {
+            duration: 180,
+            until: 180 + constants::MUTE_MARGIN_SECS,
+        };
+        assert_eq!(results, [FireResult::Empty, FireResult::Empty, bullet]);
+
+        // Fewer shots than needed to reach the bullet
+        assert_eq!(
+            roulette.clone().fire_many_at(2, UNIX_EPOCH, false),
+            [FireResult::Empty, FireResult::Empty]
+        );
+        // At least one shot
+        assert_eq!(
+            roulette.fire_many_at(0, UNIX_EPOCH, false),
+            [FireResult::Empty]
+        );
+
+        // Runs into no bullets after the last one is fired
+        roulette.fire_many_at(2, UNIX_EPOCH, false);
+        assert_eq!(
+            roulette.fire_many_at(3, UNIX_EPOCH, false),
+            [FireResult::NoBullets]
+        );
+    }
+
+    #[test]
+    fn test_spin() {
+        let config = RouletteConfig {
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        let mut roulette = config.start().unwrap();
+        while !roulette.fire().is_bullet() {}
+
+        let (shots, filled, left) = roulette.status();
+        assert!(shots > 0);
+        assert_eq!((filled, left), (1, 6 - shots));
+
+        roulette.spin();
+        assert_eq!(roulette.position, 0);
+        assert_eq!(roulette.peek(), (1, 6));
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut roulette = RouletteConfig::default().start().unwrap();
+        roulette.fire();
+        let snapshot = roulette.snapshot();
+
+        let mut restored = RouletteConfig::default().start().unwrap();
+        restored.restore(&snapshot).unwrap();
+        assert_eq!(restored.contents, roulette.contents);
+        assert_eq!(restored.position, roulette.position);
+        assert_eq!(restored.seed(), roulette.seed());
+        assert_eq!(restored.stats(), roulette.stats());
+        // Including the random number generator, so both play on identically
+        assert_eq!(restored.rng, roulette.rng);
+
+        // Mismatching chambers are rejected
+        let config = RouletteConfig {
+            chambers: 8,
+            ..Default::default()
+        };
+        let mut other = config.start().unwrap();
+        let before = other.snapshot();
+        assert!(other.restore(&snapshot).is_err());
+        assert_eq!(other.snapshot(), before);
+    }
+
+    #[test]
+    fn test_bullet_ramp() {
+        let config = RouletteConfig {
+            chambers: 6,
+            bullets: 1,
+            bullet_ramp: Some(1),
+            max_bullets: Some(3),
+            ..Default::default()
+        };
+        let mut roulette = Roulette::from_seed(config.clone(), 0).unwrap();
+        assert_eq!(roulette.info(), (1, 6));
+        let mut counts = Vec::new();
+        for _ in 0..3 {
+            roulette.reload();
+            assert_eq!(roulette.peek(), (roulette.bullets(), 6));
+            counts.push(roulette.info().0);
+        }
+        // Starting over once beyond the cap
+        assert_eq!(counts, [2, 3, 1]);
+
+        // A ramped loading survives persistence
+        roulette.reload();
+        let mut restored = Roulette::from_seed(config.clone(), 1).unwrap();
+        restored.restore(&roulette.snapshot()).unwrap();
+        assert_eq!(restored.info(), (2, 6));
+        // Disabling the ramp starts over
+        restored
+            .reconfigure(RouletteConfig {
+                bullet_ramp: None,
+                ..config.clone()
+            })
+            .unwrap();
+        assert_eq!(restored.info(), (1, 6));
+
+        // Groups can turn off a ramp of the game config
+        let group = |content: &str| toml::from_str::<GroupConfig>(content).unwrap();
+        let resolved = group("id = 1\nbullet_ramp = 0").resolve(&config).unwrap();
+        assert_eq!(resolved.bullet_ramp(), None);
+        assert_eq!(resolved.next_bullets(1), 1);
+        let resolved = group("id = 1").resolve(&config).unwrap();
+        assert_eq!(resolved.bullet_ramp(), Some(1));
+
+        let over = RouletteConfig {
+            max_bullets: Some(7),
+            ..config
+        };
+        assert_eq!(over.validate(), Err(ConfigError::InvalidMaxBullets));
+    }
+
+    #[test]
+    fn test_accessors() {
+        let config = RouletteConfig {
+            chambers: 8,
+            bullets: 3,
+            jam_probability: 0.2,
+            jam_escalation: 0.05,
+            min_mute_time: 45,
+            max_mute_time: 90,
+            ..Default::default()
+        };
+        assert_eq!(config.chambers(), 8);
+        assert_eq!(config.bullets(), 3);
+        assert_eq!(config.jam_probability(), 0.2);
+        assert_eq!(config.jam_escalation(), 0.05);
+        assert_eq!(config.min_mute_time(), 45);
+        assert_eq!(config.max_mute_time(), 90);
+
+        let roulette = config.start().unwrap();
+        assert_eq!(roulette.chambers(), 8);
+        assert_eq!(roulette.bullets(), 3);
+        assert_eq!(roulette.jam_probability(), 0.2);
+        assert_eq!(roulette.min_mute_time(), 45);
+        assert_eq!(roulette.max_mute_time(), 90);
+    }
+
+    #[test]
+    fn test_jam_escalation() {
+        let config = RouletteConfig {
+            chambers: 6,
+            bullets: 6,
+            jam_probability: 0.1,
+            jam_escalation: 0.25,
+            ..Default::default()
+        };
+        let mut roulette = config.start().unwrap();
+        let mut probability = |position| {
+            roulette.position = position;
+            roulette.jam_probability()
+        };
+        assert_eq!(probability(0), 0.1);
+        assert_eq!(probability(2), 0.6);
+        assert_eq!(probability(4), 1.0);
+        assert_eq!(probability(5), 1.0);
+
+        // Back to the base after reloading
+        roulette.reload();
+        assert_eq!(roulette.jam_probability(), 0.1);
+    }
+
+    #[test]
+    fn test_survivors() {
+        let config = RouletteConfig {
+            chambers: 5,
+            bullets: 2,
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        // Find a loading with both bullets last, so the win nears pull by pull
+        let mut roulette = (0..)
+            .map(|seed| Roulette::from_seed(config.clone(), seed).unwrap())
+            .find(|roulette| roulette.contents[3] && roulette.contents[4])
+            .unwrap();
+        for user_id in 1..=3 {
+            assert!(!roulette.only_bullets_left());
+            assert_eq!(roulette.fire(), FireResult::Empty);
+            roulette.record_player(user_id, &format!("Player {user_id}"), false);
+        }
+        assert!(roulette.only_bullets_left());
+        assert_eq!(roulette.survivors().len(), 3);
+
+        // Getting shot disqualifies a survivor, while bullets are still all that is left
+        assert!(matches!(roulette.fire(), FireResult::Bullet { .. }));
+        roulette.record_player(2, "Player 2", true);
+        assert!(roulette.only_bullets_left());
+        let names: Vec<_> = roulette
+            .survivors()
+            .iter()
+            .map(|(_, name)| name.as_str())
+            .collect();
+        assert_eq!(names, ["Player 1", "Player 3"]);
+
+        // The last bullet leaves no chambers at all
+        roulette.fire();
+        assert!(!roulette.only_bullets_left());
+        roulette.reload();
+        assert!(roulette.survivors().is_empty());
+
+        let probabilistic = RouletteConfig {
+            mode: FireMode::Probabilistic {
+                hit_probability: 1.0,
+            },
+            ..config
+        };
+        assert!(!probabilistic.start().unwrap().only_bullets_left());
+    }
+
+    #[test]
+    fn test_odds() {
+        let config = RouletteConfig {
+            chambers: 4,
+            bullets: 1,
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        // Find a loading with the bullet in the last chamber
+        let mut roulette = (0..)
+            .map(|seed| Roulette::from_seed(config.clone(), seed).unwrap())
+            .find(|roulette| roulette.contents[3])
+            .unwrap();
+        let mut odds = |position| {
+            roulette.position = position;
+            roulette.odds()
+        };
+        assert_eq!(odds(0), 0.25);
+        assert_eq!(odds(2), 0.5);
+        assert_eq!(odds(3), 1.0);
+        assert_eq!(odds(4), 0.0);
+
+        // Jams save the player
+        let jammy = RouletteConfig {
+            jam_probability: 0.2,
+            ..config
+        };
+        let mut roulette = jammy.start().unwrap();
+        assert!((roulette.odds() - 0.25 * 0.8).abs() < 1e-9);
+        while !roulette.is_empty() {
+            roulette.fire();
+        }
+        assert_eq!(roulette.odds(), 0.0);
+
+        let probabilistic = RouletteConfig {
+            mode: FireMode::Probabilistic {
+                hit_probability: 0.5,
+            },
+            jam_probability: 0.2,
+            ..Default::default()
+        };
+        assert!((probabilistic.start().unwrap().odds() - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_simulate() {
+        let config = RouletteConfig {
+            chambers: 6,
+            bullets: 1,
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        let roulette = config.start().unwrap();
+        let before = roulette.snapshot();
+        let stats = roulette.simulate(100_000);
+        assert_eq!(roulette.snapshot(), before);
+        assert_eq!(stats.jammed, 0);
+        assert_eq!(stats.empty + stats.bullet, 100_000);
+        // Expected number of pulls per loading is 3.5
+        assert!((stats.hit_rate() - 2.0 / 7.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_stats() {
+        let config = RouletteConfig {
+            chambers: 2,
+            bullets: 2,
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        let mut roulette = config.start().unwrap();
+        roulette.fire();
+        roulette.fire();
+        // Not counted, since nothing was fired
+        assert_eq!(roulette.fire(), FireResult::NoBullets);
+        roulette.reload();
+        roulette.config.jam_probability = 1.0;
+        assert_eq!(roulette.fire(), FireResult::Jammed);
+        assert_eq!(
+            roulette.stats(),
+            Stats {
+                fires: 3,
+                bullets: 2,
+                jams: 1,
+                reloads: 1,
+                mute_secs: roulette.stats().mute_secs,
+            }
+        );
+    }
+
+    #[test]
+    fn test_probabilistic() {
+        let always = RouletteConfig {
+            jam_probability: 0.0,
+            mode: FireMode::Probabilistic {
+                hit_probability: 1.0,
+            },
+            ..Default::default()
+        };
+        let mut roulette = always.start().unwrap();
+        // Never runs out, unlike the fixed loading of 2 bullets
+        for _ in 0..10 {
+            assert!(roulette.fire().is_bullet());
+        }
+        assert!(!roulette.is_empty());
+        assert_eq!(roulette.peek(), (6, 6));
+
+        let never = RouletteConfig {
+            jam_probability: 0.0,
+            mode: FireMode::Probabilistic {
+                hit_probability: 0.0,
+            },
+            ..Default::default()
+        };
+        let mut roulette = never.start().unwrap();
+        for _ in 0..10 {
+            assert_eq!(roulette.fire(), FireResult::Empty);
+        }
+        assert_eq!(roulette.peek(), (0, 6));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let default = RouletteConfig::default();
+        let group = |content: &str| toml::from_str::<GroupConfig>(content).unwrap();
+        assert!(group("id = 1\nbullets = 6").resolve(&default).is_ok());
+        // Unknown message keys are rejected, instead of silently ignored
+        assert!(toml::from_str::<GroupConfig>("id = 1\n[messages]\nsafe = \"Phew\"").is_ok());
+        assert!(toml::from_str::<GroupConfig>("id = 1\n[messages]\nsaf = \"Phew\"").is_err());
+        let config = group("id = 1\ncooldown_seconds = 30").resolve(&default);
+        assert_eq!(config.unwrap().cooldown_secs(), 30);
+        let err = |content| group(content).resolve(&default).unwrap_err();
+        assert_eq!(
+            err("id = 1\nbullets = 7"),
+            ConfigError::BulletsExceedChambers
+        );
+        assert_eq!(
+            err("id = 1\nmin_mute_time = 10"),
+            ConfigError::MuteTimeTooShort
+        );
+        assert_eq!(
+            err("id = 1\nmax_mute_time = 40"),
+            ConfigError::MinExceedsMax
+        );
+        assert_eq!(
+            err("id = 1\njam_escalation = -0.1"),
+            ConfigError::NegativeJamEscalation
+        );
+        assert_eq!(
+            err("id = 1\njam_probability = 1.5"),
+            ConfigError::InvalidJamProbability
+        );
+        assert_eq!(
+            err("id = 1\njam_probability = -0.1"),
+            ConfigError::InvalidJamProbability
+        );
+        assert!(
+            group("id = 1\nguns = [\"left\", \"right\"]")
+                .resolve(&default)
+                .is_ok()
+        );
+        for name in ["", "default", "3"] {
+            assert_eq!(
+                group(&format!("id = 1\nguns = [\"{name}\"]")).resolve(&default),
+                Err(ConfigError::ReservedGunName)
+            );
+        }
+        assert_eq!(
+            err("id = 1\nguns = [\"left\", \"left\"]"),
+            ConfigError::DuplicateGunName
+        );
+        assert_eq!(
+            err("id = 1\nmode = { kind = \"probabilistic\", hit_probability = 1.5 }"),
+            ConfigError::InvalidHitProbability
+        );
+        assert_eq!(
+            err("id = 1\ntimezone = \"Mars/Olympus\""),
+            ConfigError::InvalidTimezone
+        );
+    }
+
+    #[test]
+    fn test_misfire() {
+        let config = RouletteConfig {
+            mode: FireMode::Probabilistic {
+                hit_probability: 1.0,
+            },
+            jam_probability: 0.0,
+            misfire_probability: 0.25,
+            ..Default::default()
+        };
+        let mut roulette = Roulette::from_seed(config.clone(), 42).unwrap();
+        let mut misfires = 0_u64;
+        for _ in 0..10_000 {
+            match roulette.fire() {
+                FireResult::Misfire => misfires += 1,
+                result => assert!(result.is_bullet()),
+            }
+        }
+        assert!((2300..2700).contains(&misfires), "{misfires} misfires");
+        // Misfired bullets are not counted as fired
+        assert_eq!(roulette.stats().bullets, 10_000 - misfires);
+        // Reproducible with the same seed
+        let mut again = Roulette::from_seed(config, 42).unwrap();
+        let again = (0..10_000)
+            .filter(|_| again.fire() == FireResult::Misfire)
+            .count() as u64;
+        assert_eq!(again, misfires);
+
+        // A misfire still uses up the chamber
+        let config = RouletteConfig {
+            chambers: 2,
+            bullets: 2,
+            jam_probability: 0.0,
+            misfire_probability: 1.0,
+            ..Default::default()
+        };
+        let mut roulette = config.start().unwrap();
+        assert_eq!(roulette.odds(), 0.0);
+        assert_eq!(roulette.fire_many(2), [FireResult::Misfire]);
+        assert_eq!(roulette.fire(), FireResult::Misfire);
+        assert!(roulette.is_empty());
+
+        let invalid = RouletteConfig {
+            misfire_probability: 1.5,
+            ..Default::default()
+        };
+        assert_eq!(
+            invalid.start().unwrap_err(),
+            ConfigError::InvalidMisfireProbability
+        );
+    }
+
+    #[test]
+    fn test_discharge_on_peek() {
+        let config = RouletteConfig {
+            chambers: 3,
+            bullets: 1,
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        // Peeking doesn't advance by default
+        let mut roulette = config.clone().start().unwrap();
+        for _ in 0..10 {
+            assert!(!roulette.discharge_on_peek());
+        }
+        assert_eq!(roulette.position(), 0);
+        assert_eq!(roulette.stats().fires, 0);
+
+        // Always discharging wastes a chamber on each peek, until no bullets are left
+        let mut roulette = RouletteConfig {
+            peek_fire_probability: 1.0,
+            ..config
+        }
+        .start()
+        .unwrap();
+        let loaded = roulette.snapshot().contents;
+        let last = loaded.iter().rposition(|&loaded| loaded).unwrap();
+        for position in 0..=last {
+            assert_eq!(roulette.position(), position);
+            assert!(roulette.discharge_on_peek());
+        }
+        assert!(roulette.is_empty());
+        assert!(!roulette.discharge_on_peek());
+        assert_eq!(roulette.stats().fires, last as u64 + 1);
+        assert_eq!(roulette.stats().bullets, 0);
+    }
+
+    #[test]
+    fn test_jam_probability_bounds() {
+        let jamming = |jam_probability| RouletteConfig {
+            jam_probability,
+            ..Default::default()
+        };
+        assert_eq!(
+            jamming(f64::NAN).start().unwrap_err(),
+            ConfigError::InvalidJamProbability
+        );
+        assert_eq!(
+            jamming(1.0001).start().unwrap_err().to_string(),
+            "Jam probability must be between 0 and 1"
+        );
+
+        // Never jams
+        let mut roulette = jamming(0.0).start().unwrap();
+        while !roulette.is_empty() {
+            assert_ne!(roulette.fire(), FireResult::Jammed);
+        }
+        // Always jams
+        let mut roulette = jamming(1.0).start().unwrap();
+        for _ in 0..10 {
+            assert_eq!(roulette.fire(), FireResult::Jammed);
+        }
+    }
+
+    #[test]
+    fn test_zero_chambers_or_bullets() {
+        let zero_chambers = RouletteConfig {
+            chambers: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            zero_chambers.start().unwrap_err(),
+            ConfigError::ZeroChambers
+        );
+        let zero_bullets = RouletteConfig {
+            bullets: 0,
+            ..Default::default()
+        };
+        assert_eq!(zero_bullets.start().unwrap_err(), ConfigError::ZeroBullets);
+        let too_many_bullets = RouletteConfig {
+            bullets: 7,
+            ..Default::default()
+        };
+        assert_eq!(
+            too_many_bullets.start().unwrap_err(),
+            ConfigError::BulletsExceedChambers
+        );
+    }
+
+    #[test]
+    fn test_start_message() {
+        let group = |content: &str| toml::from_str::<GroupConfig>(content).unwrap();
+        let default = RouletteConfig::default();
+        let live = group(
+            r#"
+            id = 1
+            bullets = 2
+            chambers = 8
+            start_message = "Live: {bullets} bullets in {chambers} chambers!"
+            "#,
+        );
+        let resolved = live.resolve(&default).unwrap();
+        assert_eq!(
+            live.start_message(&resolved).unwrap(),
+            "Live: 2 bullets in 8 chambers!"
+        );
+        // Silent by default
+        assert_eq!(group("id = 2").start_message(&default), None);
+    }
+
+    #[test]
+    fn test_display() {
+        let config = RouletteConfig {
+            jam_probability: 0.07,
+            jam_escalation: 0.1,
+            misfire_probability: 0.29,
+            peek_fire_probability: 0.57,
+            mode: FireMode::Probabilistic {
+                hit_probability: 0.57,
+            },
+            ..Default::default()
+        };
+        let display = config.to_string();
+        assert!(display.contains("Jam probability: 7.0%\n"));
+        assert!(display.contains("Jam escalation: 10.0%\n"));
+        assert!(display.contains("Misfire probability: 29.0%\n"));
+        assert!(display.contains("Peek fire probability: 57.0%\n"));
+        assert!(display.contains("Mode: probabilistic (57.0% per pull)\n"));
+    }
+
+    #[test]
+    fn test_has_enough_members() {
+        let config = RouletteConfig {
+            min_members: 5,
+            ..Default::default()
+        };
+        assert!(!config.has_enough_members(4));
+        assert!(config.has_enough_members(5));
+        assert!(config.has_enough_members(100));
+        // No limit by default
+        assert!(RouletteConfig::default().has_enough_members(1));
+    }
+
+    #[test]
+    fn test_max_mute_time() {
+        let a_year = RouletteConfig {
+            max_mute_time: 365 * 24 * 3600,
+            ..Default::default()
+        };
+        assert!(a_year.validate().is_ok());
+        let (duration, until) = a_year.random_mute_until(&mut rand::rng());
+        assert!(duration <= 365 * 24 * 3600);
+        assert!(until > duration);
+
+        let over_a_year = RouletteConfig {
+            max_mute_time: 365 * 24 * 3600 + 1,
+            ..Default::default()
+        };
+        let err = over_a_year.validate().unwrap_err();
+        assert_eq!(err, ConfigError::MuteTimeTooLong);
+        assert_eq!(
+            err.to_string(),
+            "Maximum mute time must be less than or equal to 31536000 seconds (365 days)"
+        );
+    }
+
+    #[test]
+    fn test_mute_until_backward_clock() {
+        let config = RouletteConfig::default();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let before_epoch = UNIX_EPOCH - std::time::Duration::from_secs(3600);
+        let (duration, until) = config.random_mute_until_at(&mut rng, before_epoch);
+        assert_eq!(until, duration + constants::MUTE_MARGIN_SECS);
+    }
+
+    #[test]
+    fn test_jam_advances() {
+        let config = RouletteConfig {
+            chambers: 3,
+            bullets: 1,
+            jam_probability: 1.0,
+            ..Default::default()
+        };
+        // By default, the jammed chamber is retried
+        let mut roulette = config.clone().start().unwrap();
+        assert_eq!(roulette.fire(), FireResult::Jammed);
+        assert_eq!(roulette.fire(), FireResult::Jammed);
+        assert_eq!(roulette.position(), 0);
+        assert_eq!(roulette.peek(), (1, 3));
+
+        // Otherwise, it is skipped, bullet included
+        let advancing = RouletteConfig {
+            jam_advances: true,
+            ..config
+        };
+        let mut roulette = (0..)
+            .map(|seed| Roulette::from_seed(advancing.clone(), seed).unwrap())
+            .find(|roulette| roulette.contents[0])
+            .unwrap();
+        assert_eq!(roulette.fire(), FireResult::Jammed);
+        assert_eq!(roulette.position(), 1);
+        assert_eq!(roulette.peek(), (0, 2));
+        assert!(roulette.is_empty());
+        assert_eq!(roulette.fire(), FireResult::NoBullets);
+    }
+
+    #[test]
+    fn test_builder() {
+        let config = RouletteConfig::builder()
+            .chambers(8)
+            .bullets(2)
+            .jam_probability(0.0)
+            .min_mute_time(30)
+            .max_mute_time(90)
+            .build()
+            .unwrap();
+        assert_eq!(config.info(), (2, 8));
+        assert_eq!(config.jam_probability, 0.0);
+        assert_eq!((config.min_mute_time, config.max_mute_time), (30, 90));
+        // Other options are the defaults
+        assert_eq!(
+            RouletteConfig::builder().build().unwrap(),
+            RouletteConfig::default()
+        );
+
+        let build = |builder: RouletteConfigBuilder| builder.build().unwrap_err();
+        let builder = RouletteConfig::builder;
+        assert_eq!(build(builder().chambers(0)), ConfigError::ZeroChambers);
+        assert_eq!(build(builder().bullets(0)), ConfigError::ZeroBullets);
+        assert_eq!(
+            build(builder().chambers(2).bullets(3)),
+            ConfigError::BulletsExceedChambers
+        );
+        assert_eq!(
+            build(builder().min_mute_time(10)),
+            ConfigError::MuteTimeTooShort
+        );
+        assert_eq!(
+            build(builder().min_mute_time(120).max_mute_time(60)),
+            ConfigError::MinExceedsMax
+        );
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let config = RouletteConfig {
+            mode: FireMode::Probabilistic {
+                hit_probability: 0.5,
+            },
+            reactions: Some(Reactions {
+                bullet: "🤯".to_string(),
+                empty: "😎".to_string(),
+                jammed: "🤨".to_string(),
+            }),
+            ..Default::default()
+        };
+        let mut roulette = Roulette::from_seed(config, 42).unwrap();
+        roulette.fire();
+        roulette.record_player(1, "Ferris", false);
+
+        let json = serde_json::to_string(&roulette).unwrap();
+        let mut restored: Roulette = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, roulette);
+        // Including the random number generator, so both play on identically
+        assert_eq!(restored.fire(), roulette.fire());
+        assert_eq!(restored, roulette);
+
+        // Unplayable games are rejected
+        let value = serde_json::to_value(&roulette).unwrap();
+        let tampered = |pointer: &str, tampered: serde_json::Value| {
+            let mut value = value.clone();
+            *value.pointer_mut(pointer).unwrap() = tampered;
+            serde_json::from_value::<Roulette>(value)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            tampered("/position", 7.into()),
+            "Position is out of the chambers"
+        );
+        assert_eq!(
+            tampered("/contents", serde_json::json!([])),
+            "Number of chambers doesn't match the config"
+        );
+        assert_eq!(
+            tampered("/contents/0", (!roulette.contents[0]).into()),
+            "Number of bullets doesn't match the config"
+        );
+        assert_eq!(
+            tampered("/config/jam_probability", 1.5.into()),
+            "Jam probability must be between 0 and 1"
+        );
+        assert_eq!(
+            tampered("/config/peek_fire_probability", (-0.1).into()),
+            "Peek fire probability must be between 0 and 1"
+        );
+
+        let json = serde_json::to_value(FireResult::Bullet {
+            duration: 60,
+            until: 65,
+        })
+        .unwrap();
+        assert_eq!(json["Bullet"]["until"], 65);
+    }
+
+    #[test]
+    fn test_mute_until_margin() {
+        let config = RouletteConfig {
+            min_mute_time: 30,
+            max_mute_time: 40,
+            ..Default::default()
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let now = UNIX_EPOCH + std::time::Duration::from_secs(1000);
+        for _ in 0..100 {
+            let (duration, until) = config.random_mute_until_at(&mut rng, now);
+            assert!(until >= 1000 + 30 + constants::MUTE_MARGIN_SECS);
+            assert_eq!(until, 1000 + duration + constants::MUTE_MARGIN_SECS);
+        }
+    }
+
+    #[test]
+    fn test_from_seed() {
+        let mut roulette = RouletteConfig::default().start().unwrap();
+        roulette.fire();
+        roulette.reload();
+        roulette.fire();
+
+        // Replay the current loading from its seed
+        let mut replay = Roulette::from_seed(RouletteConfig::default(), roulette.seed()).unwrap();
+        assert_eq!(replay.contents, roulette.contents);
+        replay.fire();
+        assert_eq!(replay.position, roulette.position);
+        for _ in 0..6 {
+            assert_eq!(
+                replay.fire_at(UNIX_EPOCH, false),
+                roulette.fire_at(UNIX_EPOCH, false)
+            );
+        }
+    }
+
+    #[test]
+    fn test_seeded() {
+        let config = RouletteConfig {
+            jam_probability: 0.3,
+            seed: Some(42),
+            ..Default::default()
+        };
+        let play = |config: RouletteConfig| {
+            let mut roulette = config.start().unwrap();
+            let mut outcomes = Vec::new();
+            for _ in 0..50 {
+                match roulette.fire_at(UNIX_EPOCH, false) {
+                    FireResult::NoBullets => roulette.reload(),
+                    result => outcomes.push(result),
+                }
+            }
+            outcomes
+        };
+        assert_eq!(play(config.clone()), play(config));
+    }
 }
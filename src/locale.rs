@@ -0,0 +1,586 @@
+//! Bundled reply texts in each supported language.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Key of a reply text, bundled in each [`Locale`] and customizable with [`Messages`](crate::Messages).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageKey {
+    /// Reply when the player got shot, with `{name}`, `{duration}` and `{time}`.
+    Shot,
+    /// Reply when the player survived, with `{name}`.
+    Safe,
+    /// Reply when the gun got jammed, with `{name}`.
+    Jammed,
+    /// Reply when the bullet misfired, with `{name}`.
+    Misfire,
+    /// Reply when the player got shot within `grace_period_secs`, with `{name}`.
+    WarningShot,
+    /// Reply to peeking the revolver, with `{filled}` and `{left}`.
+    Peek,
+    /// Reply to peeking the next chamber in the `next` peek mode, when it is loaded.
+    PeekLoaded,
+    /// Reply to peeking the next chamber in the `next` peek mode, when it is empty.
+    PeekEmpty,
+    /// Reply when peeking discharged the gun with `peek_fire_probability`.
+    PeekFired,
+    /// Addition to the reply to peeking with `peek_shows_jam`, with `{chance}` in percent.
+    PeekJam,
+    /// Notice when the gun has been reloaded, with `{bullets}` and `{chambers}`.
+    Reloaded,
+    /// Notice when the gun has been emptied with `auto_reload` disabled.
+    Emptied,
+    /// Reply to pulling the trigger of an empty gun with `auto_reload` disabled.
+    Empty,
+    /// Announcement of the survivors winning a loading with `survivors_win`, with `{names}`.
+    Winners,
+    /// Celebration of the group reaching one of the `milestones` of total pulls, with `{fires}`.
+    Milestone,
+    /// Addition to the announcement of the winners with `auto_reload` disabled.
+    AnotherRound,
+    /// Reply when the game is paused by admins.
+    Paused,
+    /// Addition to the reply when a tournament is called off as the game got paused.
+    CalledOff,
+    /// Reply telling the chance of getting shot on the next pull, with `{percent}`.
+    Odds,
+    /// Reply to asking for the odds in the `next` peek mode, which keeps them secret.
+    OddsSecret,
+    /// Reply to `/debug` when debugging is disabled.
+    DebugDisabled,
+    /// Reply to `/debug` by a non-admin.
+    DebugAdminOnly,
+    /// Reply when the debug dump has been sent privately.
+    DebugSent,
+    /// Reply when the debug dump could not be sent privately.
+    DebugFailed,
+    /// Weekly digest, with `{fires}`, `{bullets}`, `{survivals}` and `{mute_secs}`.
+    Digest,
+    /// Addition to the weekly digest naming the player shot the most, with `{name}` and `{shot}`.
+    DigestLoser,
+    /// Reply when the gun is still jammed, with `{seconds}` left.
+    JamCooldown,
+    /// Reply when the player has to wait for `play_cooldown_secs`, with `{name}` and `{seconds}` left.
+    PlayCooldown,
+    /// Reply when the player is already within the `chicken_window_secs` of a pull, with `{name}`.
+    Holding,
+    /// Announcement of a pull within `chicken_window_secs`, with `{name}`, `{chicken}` and `{seconds}`.
+    ChickenPrompt,
+    /// Narration of each empty chamber before the last of several shots.
+    Click,
+    /// Addition to the reply when an admin got shot, as admins cannot be muted.
+    AdminNotMuted,
+    /// Reply when an admin tries to play without `admins_can_play`.
+    AdminCannotPlay,
+    /// Reply when the owner tries to play, as the owner can never be muted.
+    OwnerCannotPlay,
+    /// Reply when the player is not a current member of the group.
+    NotMember,
+    /// Reply when the gun is still empty after reloading, which should not happen.
+    SomehowEmpty,
+    /// Pinned announcement of a player surviving `jackpot_streak` pulls in a row, with `{name}` and `{streak}`.
+    Jackpot,
+    /// Reply when the named gun does not exist, with `{name}` and the available `{guns}`.
+    NoSuchGun,
+    /// Reply to showing the cylinder, with the `{chambers}` as emoji.
+    Cylinder,
+    /// Reply when the player backed out of a pull, with `{name}` and `{count}` so far.
+    ChickenedOut,
+    /// Reply to backing out without a pending pull.
+    NoPendingPull,
+    /// Reply to pausing the game by a non-admin.
+    DisableAdminOnly,
+    /// Reply when the game has been paused.
+    Disabled,
+    /// Reply to pausing the game when it is paused already.
+    AlreadyDisabled,
+    /// Reply to resuming the game by a non-admin.
+    EnableAdminOnly,
+    /// Reply when the game has been resumed.
+    Enabled,
+    /// Reply to resuming the game when it is running already.
+    AlreadyEnabled,
+    /// Reply to the leaderboard when nobody has been shot yet.
+    NobodyShot,
+    /// Heading of the leaderboard.
+    Leaderboard,
+    /// Line of the leaderboard, with `{rank}`, `{name}`, `{shot}` and `{survived}`.
+    LeaderboardEntry,
+    /// Reply to inspecting the seed by a non-admin.
+    SeedAdminOnly,
+    /// Reply to inspecting the seed, with `{seed}` and the `{position}` of the next chamber.
+    Seed,
+    /// Reply to spinning the cylinder by an admin.
+    SpinAdminOnly,
+    /// Reply when the cylinder has been spun, with `{bullets}` and `{chambers}` left.
+    Spun,
+    /// Reply with the statistics, with `{fires}`, `{bullets}`, `{jams}` and `{reloads}`.
+    Stats,
+    /// Reply to joining a tournament by an admin.
+    JoinAdminOnly,
+    /// Reply to joining when there is no tournament.
+    NoTournament,
+    /// Reply when the player has joined the tournament, with `{name}` and `{count}`.
+    Joined,
+    /// Reply when the player has joined the tournament already, with `{name}`.
+    AlreadyJoined,
+    /// Reply to joining a tournament that is no longer accepting players.
+    JoinClosed,
+    /// Reply to hosting a tournament by a non-admin.
+    TournamentAdminOnly,
+    /// Reply to hosting a tournament while one is accepting players, with `{count}` and `{seconds}` left.
+    TournamentJoining,
+    /// Announcement of a tournament, with `{join}` and `{seconds}` to join within.
+    TournamentStarted,
+    /// Reply when a tournament is called off as its gun could not be loaded.
+    TournamentBroken,
+    /// Line of the tournament results when everyone survived, with `{round}`.
+    RoundSurvived,
+    /// Line of the tournament results naming those shot, with `{round}` and `{names}`.
+    RoundShot,
+    /// Conclusion of the tournament results, with `{name}` of the champion.
+    Champion,
+    /// Conclusion of a tournament nobody joined.
+    NobodyJoined,
+    /// Conclusion of a tournament without a champion.
+    Draw,
+    /// Reply to pardoning by a non-admin.
+    PardonAdminOnly,
+    /// Reply to pardoning a bot or an anonymous admin.
+    PardonBot,
+    /// Reply to pardoning without a user.
+    PardonUsage,
+    /// Reply when the user has been pardoned, with `{name}`.
+    Pardoned,
+    /// Name of a user known only by their `{id}`.
+    User,
+    /// Reply when the user could not be unmuted, with `{name}`.
+    PardonFailed,
+    /// Reply when the bot lacks the permission to mute.
+    NoRights,
+    /// Reply when the player could not be muted otherwise, with `{name}`.
+    MuteFailed,
+    /// Reply to reloading by a non-admin.
+    ReloadAdminOnly,
+    /// Reply to surrendering by an admin, who cannot be muted.
+    SurrenderAdmin,
+    /// Reply when the player has surrendered, with `{name}`, `{duration}` and `{time}`.
+    Surrendered,
+    /// Reply to inspecting the configuration by a non-admin.
+    ConfigAdminOnly,
+}
+
+impl MessageKey {
+    /// Every key, in the order of declaration.
+    pub const ALL: [Self; 81] = [
+        Self::Shot,
+        Self::Safe,
+        Self::Jammed,
+        Self::Misfire,
+        Self::WarningShot,
+        Self::Peek,
+        Self::PeekLoaded,
+        Self::PeekEmpty,
+        Self::PeekFired,
+        Self::PeekJam,
+        Self::Reloaded,
+        Self::Emptied,
+        Self::Empty,
+        Self::Winners,
+        Self::Milestone,
+        Self::AnotherRound,
+        Self::Paused,
+        Self::CalledOff,
+        Self::Odds,
+        Self::OddsSecret,
+        Self::DebugDisabled,
+        Self::DebugAdminOnly,
+        Self::DebugSent,
+        Self::DebugFailed,
+        Self::Digest,
+        Self::DigestLoser,
+        Self::JamCooldown,
+        Self::PlayCooldown,
+        Self::Holding,
+        Self::ChickenPrompt,
+        Self::Click,
+        Self::AdminNotMuted,
+        Self::AdminCannotPlay,
+        Self::OwnerCannotPlay,
+        Self::NotMember,
+        Self::SomehowEmpty,
+        Self::Jackpot,
+        Self::NoSuchGun,
+        Self::Cylinder,
+        Self::ChickenedOut,
+        Self::NoPendingPull,
+        Self::DisableAdminOnly,
+        Self::Disabled,
+        Self::AlreadyDisabled,
+        Self::EnableAdminOnly,
+        Self::Enabled,
+        Self::AlreadyEnabled,
+        Self::NobodyShot,
+        Self::Leaderboard,
+        Self::LeaderboardEntry,
+        Self::SeedAdminOnly,
+        Self::Seed,
+        Self::SpinAdminOnly,
+        Self::Spun,
+        Self::Stats,
+        Self::JoinAdminOnly,
+        Self::NoTournament,
+        Self::Joined,
+        Self::AlreadyJoined,
+        Self::JoinClosed,
+        Self::TournamentAdminOnly,
+        Self::TournamentJoining,
+        Self::TournamentStarted,
+        Self::TournamentBroken,
+        Self::RoundSurvived,
+        Self::RoundShot,
+        Self::Champion,
+        Self::NobodyJoined,
+        Self::Draw,
+        Self::PardonAdminOnly,
+        Self::PardonBot,
+        Self::PardonUsage,
+        Self::Pardoned,
+        Self::User,
+        Self::PardonFailed,
+        Self::NoRights,
+        Self::MuteFailed,
+        Self::ReloadAdminOnly,
+        Self::SurrenderAdmin,
+        Self::Surrendered,
+        Self::ConfigAdminOnly,
+    ];
+}
+
+/// Language of the replies.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    /// English, the default.
+    #[default]
+    En,
+    /// Russian.
+    Ru,
+}
+
+impl Locale {
+    /// Get the bundled text for the given key.
+    pub fn get(self, key: MessageKey) -> &'static str {
+        match self {
+            Self::En => match key {
+                MessageKey::Shot => {
+                    "Bang! {name} was shot and muted for {duration}s, until {time}."
+                }
+                MessageKey::Safe => "Click! {name} is safe and sound.",
+                MessageKey::Jammed => "Click? You're lucky that the gun got jammed.",
+                MessageKey::Misfire => {
+                    "Pfft... The bullet misfired, {name} lives to tell the tale."
+                }
+                MessageKey::WarningShot => {
+                    "Bang! Just a warning shot past {name} for now, as the game has only just started."
+                }
+                MessageKey::Peek => {
+                    "You stole a quick glimpse at the revolver... There're {filled} filled chambers, out of {left} left-over chambers."
+                }
+                MessageKey::PeekLoaded => {
+                    "You stole a quick glimpse at the revolver... The next chamber is loaded."
+                }
+                MessageKey::PeekEmpty => {
+                    "You stole a quick glimpse at the revolver... The next chamber is empty."
+                }
+                MessageKey::PeekFired => {
+                    "Bang! The gun went off while you were peeking, wasting a chamber before you could see anything."
+                }
+                MessageKey::PeekJam => "The mechanism looks shaky ({chance}% jam chance).",
+                MessageKey::Reloaded => {
+                    "The gun has been reloaded, with {bullets} bullets in {chambers} chambers."
+                }
+                MessageKey::Emptied => "The gun is empty now, until an admin sends {reload}.",
+                MessageKey::Empty => "Click... The gun is empty. An admin needs to {reload} it.",
+                MessageKey::Winners => {
+                    "Only bullets are left! {names} survived this loading and win."
+                }
+                MessageKey::Milestone => {
+                    "🎉 The trigger has been pulled {fires} times in this group!"
+                }
+                MessageKey::AnotherRound => "An admin may {reload} for another round.",
+                MessageKey::Paused => "The game is currently paused.",
+                MessageKey::CalledOff => "The tournament is called off.",
+                MessageKey::Odds => "The chance of getting shot on the next pull is {percent}%.",
+                MessageKey::OddsSecret => {
+                    "The odds are a secret here, but you may {peek} at the next chamber."
+                }
+                MessageKey::DebugDisabled => "Debugging is disabled in this group",
+                MessageKey::DebugAdminOnly => "Only admins can debug the revolver",
+                MessageKey::DebugSent => "Sent you the dump privately",
+                MessageKey::DebugFailed => {
+                    "Failed to send you the dump privately, please start a chat with me first"
+                }
+                MessageKey::Digest => {
+                    "📊 This week: {fires} pulls, {bullets} shot, {survivals} survived, {mute_secs}s of mutes dealt."
+                }
+                MessageKey::DigestLoser => "Biggest loser so far: {name}, shot {shot} times.",
+                MessageKey::JamCooldown => {
+                    "The gun is jammed, clearing it... Try again in {seconds}s."
+                }
+                MessageKey::PlayCooldown => "{name}, you can play again in {seconds}s.",
+                MessageKey::Holding => "{name} is already holding the revolver.",
+                MessageKey::ChickenPrompt => {
+                    "{name} raises the revolver... Send {chicken} within {seconds}s to back out."
+                }
+                MessageKey::Click => "Click...",
+                MessageKey::AdminNotMuted => "But admins cannot be muted.",
+                MessageKey::AdminCannotPlay => "Cannot play roulette as an admin",
+                MessageKey::OwnerCannotPlay => "Cannot play roulette as the owner",
+                MessageKey::NotMember => "You're not a current member of this group",
+                MessageKey::SomehowEmpty => "Click? The gun is somehow empty...",
+                MessageKey::Jackpot => "Jackpot! {name} has survived {streak} pulls in a row!",
+                MessageKey::NoSuchGun => "There's no gun named \"{name}\". Available guns: {guns}.",
+                MessageKey::Cylinder => "The cylinder: {chambers}",
+                MessageKey::ChickenedOut => "{name} chickened out! That's {count} times so far.",
+                MessageKey::NoPendingPull => "You have no pending pull to back out of.",
+                MessageKey::DisableAdminOnly => "Only admins can pause the game",
+                MessageKey::Disabled => "The game has been paused.",
+                MessageKey::AlreadyDisabled => "The game is already paused.",
+                MessageKey::EnableAdminOnly => "Only admins can resume the game",
+                MessageKey::Enabled => "The game has been resumed.",
+                MessageKey::AlreadyEnabled => "The game is already running.",
+                MessageKey::NobodyShot => "Nobody has been shot yet.",
+                MessageKey::Leaderboard => "Unluckiest players:",
+                MessageKey::LeaderboardEntry => {
+                    "{rank}. {name}: shot {shot} times, survived {survived} times"
+                }
+                MessageKey::SeedAdminOnly => "Only admins can inspect the seed",
+                MessageKey::Seed => {
+                    "The current loading has seed {seed}, with {position} chambers fired."
+                }
+                MessageKey::SpinAdminOnly => "Cannot spin the revolver as an admin",
+                MessageKey::Spun => {
+                    "The cylinder spins... There're now {bullets} bullets in {chambers} chambers."
+                }
+                MessageKey::Stats => {
+                    "So far: {fires} pulls, {bullets} shot, {jams} jammed, {reloads} reloads."
+                }
+                MessageKey::JoinAdminOnly => "Cannot join a tournament as an admin",
+                MessageKey::NoTournament => "There is no ongoing tournament.",
+                MessageKey::Joined => "{name} has joined the tournament, as player #{count}.",
+                MessageKey::AlreadyJoined => "{name} has already joined the tournament.",
+                MessageKey::JoinClosed => "The tournament is no longer accepting players.",
+                MessageKey::TournamentAdminOnly => "Only admins can host a tournament",
+                MessageKey::TournamentJoining => {
+                    "{count} players have joined the tournament, which starts in {seconds}s."
+                }
+                MessageKey::TournamentStarted => {
+                    "A tournament has started! Send {join} within {seconds}s to take part."
+                }
+                MessageKey::TournamentBroken => {
+                    "The tournament is called off, as the gun is broken."
+                }
+                MessageKey::RoundSurvived => "Round {round}: everyone survived.",
+                MessageKey::RoundShot => "Round {round}: {names} got shot.",
+                MessageKey::Champion => "{name} is the champion!",
+                MessageKey::NobodyJoined => "Nobody joined the tournament.",
+                MessageKey::Draw => "The tournament ended in a draw.",
+                MessageKey::PardonAdminOnly => "Sorry, only admins can pardon",
+                MessageKey::PardonBot => "Bots and anonymous admins cannot be pardoned",
+                MessageKey::PardonUsage => {
+                    "Reply to a message of the user to pardon, or give their user ID"
+                }
+                MessageKey::Pardoned => "{name} has been pardoned and may speak again.",
+                MessageKey::User => "User {id}",
+                MessageKey::PardonFailed => "Failed to pardon {name}.",
+                MessageKey::NoRights => {
+                    "I can no longer mute people here. Please grant me the permission to ban users."
+                }
+                MessageKey::MuteFailed => "Failed to mute {name}, who got away this time.",
+                MessageKey::ReloadAdminOnly => "Sorry, only admins can reload the revolver",
+                MessageKey::SurrenderAdmin => "Admins cannot surrender, as they cannot be muted",
+                MessageKey::Surrendered => {
+                    "{name} waves a white flag and backs away from the table. See you in {duration}s, at {time}."
+                }
+                MessageKey::ConfigAdminOnly => "Only admins can inspect the configuration",
+            },
+            Self::Ru => match key {
+                MessageKey::Shot => "Бах! {name} получает пулю и молчит {duration} с, до {time}.",
+                MessageKey::Safe => "Щёлк! {name} цел(а) и невредим(а).",
+                MessageKey::Jammed => "Щёлк? Повезло, револьвер заклинило.",
+                MessageKey::Misfire => "Пфф... Осечка! {name} остаётся в живых.",
+                MessageKey::WarningShot => {
+                    "Бах! Пока это лишь предупредительный выстрел мимо {name}, ведь игра только началась."
+                }
+                MessageKey::Peek => {
+                    "Ты украдкой заглядываешь в барабан... Заряженных гнёзд: {filled}, всего осталось: {left}."
+                }
+                MessageKey::PeekLoaded => {
+                    "Ты украдкой заглядываешь в барабан... Следующее гнездо заряжено."
+                }
+                MessageKey::PeekEmpty => {
+                    "Ты украдкой заглядываешь в барабан... Следующее гнездо пустое."
+                }
+                MessageKey::PeekFired => {
+                    "Бах! Револьвер выстрелил, пока ты заглядывал(а) в барабан, и одно гнездо пропало зря."
+                }
+                MessageKey::PeekJam => {
+                    "Механизм выглядит ненадёжно (вероятность заклинивания {chance}%)."
+                }
+                MessageKey::Reloaded => {
+                    "Револьвер перезаряжен: патронов {bullets}, гнёзд {chambers}."
+                }
+                MessageKey::Emptied => "Револьвер пуст, пока админ не отправит {reload}.",
+                MessageKey::Empty => {
+                    "Щёлк... Револьвер пуст. Админу нужно перезарядить его: {reload}."
+                }
+                MessageKey::Winners => {
+                    "Остались только патроны! Победители, пережившие этот заряд: {names}."
+                }
+                MessageKey::Milestone => "🎉 В этой группе спусковой крючок нажат уже {fires} раз!",
+                MessageKey::AnotherRound => "Админ может отправить {reload} для нового раунда.",
+                MessageKey::Paused => "Игра сейчас на паузе.",
+                MessageKey::CalledOff => "Турнир отменён.",
+                MessageKey::Odds => "Шанс получить пулю при следующем выстреле: {percent}%.",
+                MessageKey::OddsSecret => {
+                    "Шансы здесь держатся в секрете, но можно заглянуть в следующее гнездо: {peek}."
+                }
+                MessageKey::DebugDisabled => "Отладка в этой группе отключена",
+                MessageKey::DebugAdminOnly => "Только админы могут отлаживать револьвер",
+                MessageKey::DebugSent => "Дамп отправлен тебе в личные сообщения",
+                MessageKey::DebugFailed => {
+                    "Не удалось отправить тебе дамп в личные сообщения, сначала начни чат со мной"
+                }
+                MessageKey::Digest => {
+                    "📊 За неделю: выстрелов {fires}, попаданий {bullets}, выживших {survivals}, молчания {mute_secs} с."
+                }
+                MessageKey::DigestLoser => "Главный неудачник: {name}, получил(а) пулю {shot} раз.",
+                MessageKey::JamCooldown => {
+                    "Револьвер заклинило, его чистят... Попробуй снова через {seconds} с."
+                }
+                MessageKey::PlayCooldown => "{name}, можно сыграть снова через {seconds} с.",
+                MessageKey::Holding => "{name} уже держит револьвер.",
+                MessageKey::ChickenPrompt => {
+                    "{name} поднимает револьвер... Отправь {chicken} в течение {seconds} с, чтобы отступить."
+                }
+                MessageKey::Click => "Щёлк...",
+                MessageKey::AdminNotMuted => "Но админов нельзя заглушить.",
+                MessageKey::AdminCannotPlay => "Админы не могут играть в рулетку",
+                MessageKey::OwnerCannotPlay => "Владелец не может играть в рулетку",
+                MessageKey::NotMember => "Ты не состоишь в этой группе",
+                MessageKey::SomehowEmpty => "Щёлк? Револьвер почему-то пуст...",
+                MessageKey::Jackpot => "Джекпот! {name} выживает {streak} выстрелов подряд!",
+                MessageKey::NoSuchGun => {
+                    "Револьвера с именем \"{name}\" нет. Доступные револьверы: {guns}."
+                }
+                MessageKey::Cylinder => "Барабан: {chambers}",
+                MessageKey::ChickenedOut => "{name} струсил(а)! Уже {count} раз.",
+                MessageKey::NoPendingPull => "Тебе не от чего отступать.",
+                MessageKey::DisableAdminOnly => "Только админы могут ставить игру на паузу",
+                MessageKey::Disabled => "Игра поставлена на паузу.",
+                MessageKey::AlreadyDisabled => "Игра уже на паузе.",
+                MessageKey::EnableAdminOnly => "Только админы могут возобновлять игру",
+                MessageKey::Enabled => "Игра возобновлена.",
+                MessageKey::AlreadyEnabled => "Игра уже идёт.",
+                MessageKey::NobodyShot => "Пока никто не получил пулю.",
+                MessageKey::Leaderboard => "Самые невезучие игроки:",
+                MessageKey::LeaderboardEntry => {
+                    "{rank}. {name}: пуль {shot}, выжил(а) {survived} раз"
+                }
+                MessageKey::SeedAdminOnly => "Только админы могут смотреть зерно",
+                MessageKey::Seed => "Зерно текущего заряда: {seed}, выстрелено гнёзд: {position}.",
+                MessageKey::SpinAdminOnly => "Админы не могут крутить барабан",
+                MessageKey::Spun => {
+                    "Барабан крутится... Теперь патронов {bullets}, гнёзд {chambers}."
+                }
+                MessageKey::Stats => {
+                    "Пока что: выстрелов {fires}, попаданий {bullets}, заклиниваний {jams}, перезарядок {reloads}."
+                }
+                MessageKey::JoinAdminOnly => "Админы не могут участвовать в турнире",
+                MessageKey::NoTournament => "Сейчас турнира нет.",
+                MessageKey::Joined => "{name} вступает в турнир игроком №{count}.",
+                MessageKey::AlreadyJoined => "{name} уже участвует в турнире.",
+                MessageKey::JoinClosed => "Турнир больше не принимает игроков.",
+                MessageKey::TournamentAdminOnly => "Только админы могут проводить турнир",
+                MessageKey::TournamentJoining => {
+                    "Игроков в турнире: {count}, он начнётся через {seconds} с."
+                }
+                MessageKey::TournamentStarted => {
+                    "Турнир начался! Отправь {join} в течение {seconds} с, чтобы участвовать."
+                }
+                MessageKey::TournamentBroken => "Турнир отменён, револьвер неисправен.",
+                MessageKey::RoundSurvived => "Раунд {round}: все выжили.",
+                MessageKey::RoundShot => "Раунд {round}: пулю получают {names}.",
+                MessageKey::Champion => "{name} становится чемпионом!",
+                MessageKey::NobodyJoined => "Никто не вступил в турнир.",
+                MessageKey::Draw => "Турнир закончился вничью.",
+                MessageKey::PardonAdminOnly => "Только админы могут миловать",
+                MessageKey::PardonBot => "Ботов и анонимных админов нельзя помиловать",
+                MessageKey::PardonUsage => {
+                    "Ответь на сообщение пользователя, которого нужно помиловать, или укажи его ID"
+                }
+                MessageKey::Pardoned => "{name} помилован(а) и снова может говорить.",
+                MessageKey::User => "Пользователь {id}",
+                MessageKey::PardonFailed => "Не удалось помиловать {name}.",
+                MessageKey::NoRights => {
+                    "Я больше не могу заглушать здесь людей. Пожалуйста, дай мне право блокировать пользователей."
+                }
+                MessageKey::MuteFailed => "Не удалось заглушить {name}, в этот раз повезло.",
+                MessageKey::ReloadAdminOnly => "Только админы могут перезаряжать револьвер",
+                MessageKey::SurrenderAdmin => "Админы не могут сдаться, ведь их нельзя заглушить",
+                MessageKey::Surrendered => {
+                    "{name} выбрасывает белый флаг и отходит от стола. Увидимся через {duration} с, в {time}."
+                }
+                MessageKey::ConfigAdminOnly => "Только админы могут смотреть настройки",
+            },
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::En => write!(f, "en"),
+            Self::Ru => write!(f, "ru"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get() {
+        assert_eq!(
+            Locale::En.get(MessageKey::Safe),
+            "Click! {name} is safe and sound."
+        );
+        assert_ne!(
+            Locale::Ru.get(MessageKey::Safe),
+            Locale::En.get(MessageKey::Safe)
+        );
+    }
+
+    #[test]
+    fn test_translations() {
+        let placeholders = |text: &str| {
+            let mut names: Vec<_> = text
+                .split('{')
+                .skip(1)
+                .filter_map(|rest| rest.split_once('}'))
+                .map(|(name, _)| name.to_string())
+                .collect();
+            names.sort();
+            names
+        };
+        // Every text is translated, with the same placeholders
+        for key in MessageKey::ALL {
+            let (en, ru) = (Locale::En.get(key), Locale::Ru.get(key));
+            assert_ne!(en, ru, "{key:?}");
+            assert_eq!(placeholders(en), placeholders(ru), "{key:?}");
+        }
+    }
+}
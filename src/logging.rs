@@ -0,0 +1,73 @@
+//! Formatting of log records.
+
+use jiff::Timestamp;
+use log::Record;
+use serde_json::json;
+
+/// Format of log records, chosen by the `RUST_LOG_FORMAT` environment variable.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines, the default.
+    #[default]
+    Text,
+    /// One JSON object per line, for log aggregators.
+    Json,
+}
+
+impl LogFormat {
+    /// Parse the format from the value of `RUST_LOG_FORMAT`, case-insensitively.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "text" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Format the record as a JSON object, with its level, timestamp, target and message.
+pub fn json_line(record: &Record, timestamp: Timestamp) -> String {
+    json!({
+        "level": record.level().as_str(),
+        "timestamp": timestamp.to_string(),
+        "target": record.target(),
+        "message": record.args().to_string(),
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Level;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(LogFormat::parse("text"), Some(LogFormat::Text));
+        assert_eq!(LogFormat::parse("JSON"), Some(LogFormat::Json));
+        assert_eq!(LogFormat::parse("xml"), None);
+    }
+
+    #[test]
+    fn test_json_line() {
+        let line = json_line(
+            &Record::builder()
+                .level(Level::Warn)
+                .target("rustacean_roulette")
+                .args(format_args!("Group \"<-100>\"\nnot activated"))
+                .build(),
+            Timestamp::UNIX_EPOCH,
+        );
+        assert!(!line.contains('\n'));
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "level": "WARN",
+                "timestamp": "1970-01-01T00:00:00Z",
+                "target": "rustacean_roulette",
+                "message": "Group \"<-100>\"\nnot activated",
+            })
+        );
+    }
+}
@@ -1,122 +1,537 @@
 use env_logger::Env;
 use frankenstein::{
-    AsyncTelegramApi, Error,
+    AsyncTelegramApi,
     client_reqwest::Bot,
-    methods::{GetChatMemberParams, GetChatParams, GetUpdatesParams, SendMessageParams},
-    types::{ChatMember, ChatType, ReplyParameters},
-    updates::UpdateContent,
+    methods::{
+        DeleteWebhookParams, GetChatMemberCountParams, GetChatMemberParams, GetChatParams,
+        GetUpdatesParams, LeaveChatParams, SendMessageParams, SetWebhookParams,
+    },
+    types::{ChatMember, ChatType},
+    updates::Update,
 };
-use log::{debug, error, info};
+use jiff::Timestamp;
+use log::{debug, error, info, warn};
 use rustacean_roulette::{
-    Commands, Config, GroupConfig, Roulette, RouletteConfig, init_commands_and_rights,
+    Config, ConfigFormat, Group, GroupConfig, Mode, RouletteConfig, Triggers, config, digest,
+    init_commands_and_rights,
+    logging::{self, LogFormat},
+    members,
+    ratelimit::RateLimiter,
+    reset,
+    server::{self, Health, Webhook},
+    state::{State, StateWriter},
+    update::{Context, next_backoff},
+};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    io::Write,
+    path::Path,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tokio::{
+    sync::{
+        Mutex,
+        mpsc::{Receiver, channel},
+    },
+    task::JoinSet,
+    time::sleep,
 };
-use std::{collections::HashMap, io::Write};
-use tokio::sync::Mutex;
-use toml::de;
 
 #[tokio::main]
-async fn main() -> Result<(), Error> {
+async fn main() {
     setup_logger();
+    let args = parse_args();
+    if args.check_config {
+        check_config(&args.config_paths);
+    }
 
+    let config = read_config(&args.config_paths).unwrap_or_else(|e| exit_with(e));
+    if let Err(err) = config.validate() {
+        exit_with(err);
+    }
     let Config {
         token,
         game: default_config,
         groups,
-    } = read_config();
+        reply_ttl_secs,
+        leave_unusable_chats,
+        process_edits,
+        private_reply,
+        state_file,
+        state_flush_secs,
+        http_addr,
+        metrics_addr,
+        health_threshold_secs,
+        mode,
+        webhook_url,
+        webhook_path,
+        webhook_secret,
+        stats_reset_hour,
+        command_triggers,
+        dry_run,
+        digest_weekday,
+        digest_hour,
+    } = config;
+    let state = match &state_file {
+        Some(path) => State::load(path).unwrap_or_else(|e| {
+            exit_with(format!(
+                "Failed to load state file ({}): {e}",
+                path.display()
+            ))
+        }),
+        None => State::default(),
+    };
+
+    let triggers = Triggers::new(&command_triggers).unwrap_or_else(|e| exit_with(e));
 
     // Create a new Telegram Bot
-    let bot: &_ = Box::leak(Box::new(Bot::new(&token)));
-    let me = bot.get_me().await?.result;
+    let bot = Arc::new(Bot::new(&token));
+    let me = match bot.get_me().await {
+        Ok(response) => response.result,
+        Err(err) => exit_with(format!(
+            "Failed to get the bot info, is the token valid? {err}"
+        )),
+    };
     let Some(username) = me.username else {
-        panic!("Failed to get bot username");
+        exit_with("Failed to get bot username");
     };
 
-    init_commands_and_rights(bot).await?;
-    let group_data = init_group_data(bot, me.id, default_config, groups).await;
-    let group_data: &_ = Box::leak(Box::new(group_data));
+    let debug = lists_debug(&default_config, &groups);
+    if let Err(err) = init_commands_and_rights(&bot, &triggers, debug).await {
+        exit_with(format!("Failed to set up commands and rights: {err}"));
+    }
+    let group_data = init_group_data(
+        &bot,
+        me.id,
+        default_config,
+        groups,
+        leave_unusable_chats,
+        &state,
+    )
+    .await;
+    let group_data = Arc::new(group_data);
     info!("Bot started: @{username}");
 
-    // Handle incoming messages
-    let mut update_params = GetUpdatesParams::builder().build();
+    // Flush the state periodically
+    let state_writer = state_file.map(|path| Arc::new(StateWriter::new(path)));
+    if let Some(writer) = &state_writer {
+        let writer = writer.clone();
+        let group_data = group_data.clone();
+        let period = Duration::from_secs(state_flush_secs.max(1));
+        tokio::spawn(async move { writer.run(&group_data, period).await });
+    }
+
+    // Reload the config on SIGHUP
+    let triggers = Arc::new(RwLock::new(triggers));
+    #[cfg(unix)]
+    {
+        let config_paths = args.config_paths.clone();
+        let bot = bot.clone();
+        let group_data = group_data.clone();
+        let triggers = triggers.clone();
+        tokio::spawn(
+            async move { reload_on_hangup(&config_paths, &bot, &group_data, &triggers).await },
+        );
+    }
+
+    // Reset per-user stats and cooldowns daily
+    if let Some(hour) = stats_reset_hour {
+        let group_data = group_data.clone();
+        tokio::spawn(async move { reset::run(&group_data, hour).await });
+    }
+
+    // Pause and resume groups as their member count crosses `min_members`
+    {
+        let bot = bot.clone();
+        let group_data = group_data.clone();
+        tokio::spawn(async move { members::run(&bot, &group_data).await });
+    }
+
+    // Post the weekly digest
+    if let Some(weekday) = digest_weekday {
+        let bot = bot.clone();
+        let group_data = group_data.clone();
+        tokio::spawn(async move { digest::run(&bot, &group_data, weekday, digest_hour).await });
+    }
+
+    // Export metrics
+    if let Some(addr) = metrics_addr {
+        let group_data = group_data.clone();
+        tokio::spawn(async move {
+            if let Err(err) = server::serve_metrics(addr, group_data).await {
+                error!("Metrics exporter failed: {err}");
+            }
+        });
+    }
+
+    // Receive updates by long polling, or from the webhook
+    let (mut source, webhook) = match mode {
+        Mode::Polling => {
+            // A webhook left over from webhook mode would make `getUpdates` fail
+            if let Err(err) = bot
+                .delete_webhook(&DeleteWebhookParams::builder().build())
+                .await
+            {
+                exit_with(format!("Failed to delete the webhook: {err}"));
+            }
+            (
+                Updates::Polling(GetUpdatesParams::builder().build(), None),
+                None,
+            )
+        }
+        Mode::Webhook => {
+            let Some(url) = webhook_url else {
+                exit_with("`webhook_url` is required in webhook mode");
+            };
+            if http_addr.is_none() {
+                exit_with("`http_addr` is required in webhook mode");
+            }
+            let set_webhook_param = SetWebhookParams::builder()
+                .url(url)
+                .maybe_secret_token(webhook_secret.clone())
+                .build();
+            if let Err(err) = bot.set_webhook(&set_webhook_param).await {
+                exit_with(format!("Failed to set the webhook: {err}"));
+            }
+            let (sender, receiver) = channel(server::WEBHOOK_QUEUE);
+            let webhook = Webhook {
+                path: webhook_path,
+                secret: webhook_secret,
+                sender,
+            };
+            (Updates::Webhook(receiver), Some(Arc::new(webhook)))
+        }
+    };
+
+    // Serve HTTP endpoints
+    let threshold = match mode {
+        Mode::Polling => Duration::from_secs(health_threshold_secs),
+        // Updates are pushed only when there are any, so staying up is all that can be checked
+        Mode::Webhook => Duration::MAX,
+    };
+    let health = Arc::new(Health::new(threshold));
+    if let Some(addr) = http_addr {
+        let health = health.clone();
+        tokio::spawn(async move {
+            if let Err(err) = server::serve(addr, health, webhook).await {
+                error!("HTTP server failed: {err}");
+            }
+        });
+    }
+
+    // Handle incoming updates, until asked to shut down
+    let context = Arc::new(Context {
+        bot: bot.clone(),
+        username,
+        triggers,
+        group_data: group_data.clone(),
+        state_writer: state_writer.clone(),
+        reply_ttl_secs,
+        process_edits,
+        private_reply,
+        limiter: RateLimiter::default(),
+        dry_run,
+    });
+    let mut tasks = JoinSet::new();
+    dispatch(
+        &mut tasks,
+        shutdown_signal(),
+        async || source.next(&bot, &health).await,
+        |update| context.clone().process(update),
+    )
+    .await;
+
+    // Let in-flight commands finish, e.g. restricting a player who just got shot
+    info!(
+        "Shutting down, waiting for {} pending commands",
+        tasks.len()
+    );
+    while tasks.join_next().await.is_some() {}
+    if let Some(writer) = &state_writer
+        && let Err(err) = writer.flush(&group_data).await
+    {
+        error!("Failed to save state file on shutdown: {err}");
+    }
+    info!("Bot stopped");
+}
+
+/// Log the error and exit with a nonzero code, for failures the bot cannot start with.
+fn exit_with(message: impl Display) -> ! {
+    error!("{message}");
+    std::process::exit(1);
+}
+
+/// Spawn a task handling each incoming update, until `shutdown` resolves or no more updates come.
+async fn dispatch<F>(
+    tasks: &mut JoinSet<()>,
+    shutdown: impl Future<Output = ()>,
+    mut next: impl AsyncFnMut() -> Option<Vec<Update>>,
+    handle: impl Fn(Update) -> F,
+) where
+    F: Future<Output = ()> + Send + 'static,
+{
+    tokio::pin!(shutdown);
     loop {
-        match bot.get_updates(&update_params).await {
-            Ok(updates) => {
-                // Update offset
-                let Some(last) = updates.result.last() else {
-                    continue;
-                };
-                update_params.offset.replace((last.update_id + 1).into());
-                // Process each update
-                for update in updates.result {
-                    debug!("Received update: {update:?}");
-                    let UpdateContent::Message(msg) = update.content else {
-                        continue;
-                    };
-                    // Whitelist check
-                    if group_data.get(&msg.chat.id).is_none() {
-                        debug!("Received message from non-whitelisted chat: {msg:?}");
-                        continue;
-                    }
+        let updates = tokio::select! {
+            () = &mut shutdown => break,
+            updates = next() => updates,
+        };
+        // Reap finished tasks
+        while tasks.try_join_next().is_some() {}
+        let Some(updates) = updates else {
+            error!("Webhook server stopped, shutting down");
+            break;
+        };
+        for update in updates {
+            tasks.spawn(handle(update));
+        }
+    }
+}
 
-                    let text = msg.text.as_ref();
-                    let Some(command) = Commands::parse(text, &username) else {
-                        debug!("Not a command: {text:?}");
-                        continue;
-                    };
-                    tokio::spawn(async move {
-                        let chat_id = msg.chat.id;
-                        let message_id = msg.message_id;
-                        let roulette = group_data.get(&chat_id).unwrap();
-                        let reply = command.execute(bot, msg, roulette).await;
-                        let Some(reply) = reply else {
-                            return;
-                        };
-                        let reply_param = ReplyParameters::builder().message_id(message_id).build();
-                        let send_message_param = SendMessageParams::builder()
-                            .chat_id(chat_id)
-                            .text(reply)
-                            .reply_parameters(reply_param)
-                            .build();
-                        if let Err(err) = bot.send_message(&send_message_param).await {
-                            error!("Failed to send message: {err}");
-                        }
-                    });
-                }
+/// Wait for a shutdown signal, i.e. Ctrl-C, or SIGTERM on Unix.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            error!("Failed to listen for Ctrl-C: {err}");
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{SignalKind, signal};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
             }
             Err(err) => {
-                error!("Error getting updates: {err}");
+                error!("Failed to listen for SIGTERM: {err}");
+                std::future::pending::<()>().await;
             }
         }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => info!("Received Ctrl-C"),
+        () = terminate => info!("Received SIGTERM"),
     }
 }
 
-/// Setup the logger.
+/// Source of incoming updates.
+enum Updates {
+    /// Long polling, with the parameters of the next request, and the delay before retrying if the last one failed.
+    Polling(GetUpdatesParams, Option<Duration>),
+    /// Updates pushed to the webhook.
+    Webhook(Receiver<Update>),
+}
+
+impl Updates {
+    /// Wait for the next batch of updates, or `None` if no more updates will come.
+    async fn next(&mut self, bot: &Bot, health: &Health) -> Option<Vec<Update>> {
+        match self {
+            Self::Polling(update_params, backoff) => match bot.get_updates(update_params).await {
+                Ok(updates) => {
+                    health.record_poll();
+                    *backoff = None;
+                    // Update offset
+                    if let Some(last) = updates.result.last() {
+                        update_params.offset.replace((last.update_id + 1).into());
+                    }
+                    Some(updates.result)
+                }
+                Err(err) => {
+                    // Back off, so as not to hammer the API during an outage
+                    let delay = next_backoff(*backoff);
+                    *backoff = Some(delay);
+                    error!(
+                        "Error getting updates, retrying in {}s: {err}",
+                        delay.as_secs()
+                    );
+                    sleep(delay).await;
+                    Some(Vec::new())
+                }
+            },
+            Self::Webhook(receiver) => receiver.recv().await.map(|update| vec![update]),
+        }
+    }
+}
+
+/// Setup the logger, in the format given by `RUST_LOG_FORMAT` (`text` or `json`).
 fn setup_logger() {
-    env_logger::Builder::from_env(Env::default().default_filter_or("info"))
-        .format(|buf, record| {
+    let format = match std::env::var("RUST_LOG_FORMAT") {
+        Ok(value) => LogFormat::parse(&value).unwrap_or_else(|| {
+            // The logger is not ready yet
+            eprintln!("Unknown log format `{value}`, expected `text` or `json`");
+            std::process::exit(1);
+        }),
+        Err(_) => LogFormat::default(),
+    };
+    let mut builder = env_logger::Builder::from_env(Env::default().default_filter_or("info"));
+    match format {
+        LogFormat::Text => builder.format(|buf, record| {
             let level = record.level();
             let style = buf.default_level_style(level);
             writeln!(buf, "[{style}{level}{style:#}] {}", record.args())
-        })
-        .init();
-}
-
-/// Read the config file and parse it into a `Config` struct.
-fn read_config() -> Config {
-    // Read path to the config file as the first argument, default to "config.toml"
-    let config_path = std::env::args()
-        .nth(1)
-        .unwrap_or_else(|| "config.toml".to_string());
-    let config_toml = std::fs::read_to_string(&config_path)
-        .unwrap_or_else(|_| panic!("Failed to read config file: {config_path}"));
-
-    // Parse the config file
-    let config: Config = match de::from_str(&config_toml) {
-        Ok(config) => config,
-        Err(e) => panic!("Failed to parse config file ({config_path}): {e}"),
+        }),
+        LogFormat::Json => builder.format(|buf, record| {
+            writeln!(buf, "{}", logging::json_line(record, Timestamp::now()))
+        }),
+    };
+    builder.init();
+}
+
+/// Command line arguments.
+struct Args {
+    /// Paths to the config files, merged in order.
+    config_paths: Vec<String>,
+    /// Only check the config files, without starting the bot.
+    check_config: bool,
+}
+
+/// Parse the command line arguments.
+///
+/// Config files could be given by `--config <path>` (repeatable) or as positional arguments, defaulting to "config.toml".
+/// `--check-config` only checks them.
+fn parse_args() -> Args {
+    let mut config_paths = Vec::new();
+    let mut check_config = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--config" || arg == "-c" {
+            let Some(path) = args.next() else {
+                exit_with(format!("Missing path after `{arg}`"));
+            };
+            config_paths.push(path);
+        } else if let Some(path) = arg.strip_prefix("--config=") {
+            config_paths.push(path.to_string());
+        } else if arg == "--check-config" {
+            check_config = true;
+        } else if arg.starts_with('-') {
+            exit_with(format!("Unknown argument: {arg}"));
+        } else {
+            config_paths.push(arg);
+        }
+    }
+    if config_paths.is_empty() {
+        config_paths.push("config.toml".to_string());
+    }
+
+    Args {
+        config_paths,
+        check_config,
+    }
+}
+
+/// Check the config files, printing `OK` or the problems found, then exit without contacting Telegram.
+fn check_config(config_paths: &[String]) -> ! {
+    let errors = match read_config(config_paths) {
+        Ok(config) => config::check(&config),
+        Err(err) => vec![err],
     };
+    if errors.is_empty() {
+        println!("OK");
+        std::process::exit(0);
+    }
+    for error in errors {
+        eprintln!("{error}");
+    }
+    std::process::exit(1);
+}
 
-    config
+/// Read the config files and merge them into a `Config` struct.
+fn read_config(config_paths: &[String]) -> Result<Config, String> {
+    let mut sources = Vec::with_capacity(config_paths.len());
+    for config_path in config_paths {
+        let format = ConfigFormat::from_path(Path::new(config_path))
+            .map_err(|e| format!("Failed to detect config format ({config_path}): {e}"))?;
+        let config_content = std::fs::read_to_string(config_path)
+            .map_err(|_| format!("Failed to read config file: {config_path}"))?;
+        sources.push((format, config_content));
+    }
+
+    // Parse and merge the config files
+    let sources = sources
+        .iter()
+        .map(|(format, content)| (*format, content.as_str()));
+    let mut config = config::merge(sources).map_err(|e| {
+        format!(
+            "Failed to parse config files ({}): {e}",
+            config_paths.join(", ")
+        )
+    })?;
+    config::resolve_token(&mut config, std::env::var(config::TOKEN_ENV).ok())?;
+    Ok(config)
+}
+
+/// Whether to list `/debug`, which is the case if any group may use it, as commands are listed for all groups alike.
+fn lists_debug(default_config: &RouletteConfig, groups: &[GroupConfig]) -> bool {
+    groups.iter().any(|group_config| {
+        group_config
+            .resolve(default_config)
+            .is_ok_and(|resolved| resolved.debug_enabled())
+    })
+}
+
+/// Reload the game configs of activated groups and the command triggers on each SIGHUP, keeping the games going.
+///
+/// Other settings, as well as which groups are activated, only take effect after a restart.
+#[cfg(unix)]
+async fn reload_on_hangup(
+    config_paths: &[String],
+    bot: &Bot,
+    group_data: &HashMap<i64, Mutex<Group>>,
+    triggers: &RwLock<Triggers>,
+) {
+    use tokio::signal::unix::{SignalKind, signal};
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(err) => {
+            error!("Failed to listen for SIGHUP: {err}");
+            return;
+        }
+    };
+    while hangup.recv().await.is_some() {
+        info!("Received SIGHUP, reloading config");
+        let config = match read_config(config_paths) {
+            Ok(config) => config,
+            Err(err) => {
+                error!("{err}, keeping the current config");
+                continue;
+            }
+        };
+        match Triggers::new(&config.command_triggers) {
+            Ok(remapped) if remapped != *triggers.read().unwrap() => {
+                let debug = lists_debug(&config.game, &config.groups);
+                if let Err(err) = init_commands_and_rights(bot, &remapped, debug).await {
+                    error!("Failed to list the remapped commands: {err}");
+                }
+                *triggers.write().unwrap() = remapped;
+                info!("Command triggers remapped");
+            }
+            Ok(_) => {}
+            Err(err) => error!("{err}, keeping the current command triggers"),
+        }
+        for group_config in config.groups {
+            let group_id = group_config.id;
+            let Some(group) = group_data.get(&group_id) else {
+                info!("Group <{group_id}> is not activated, ignoring until restart");
+                continue;
+            };
+            let result = match group_config.resolve(&config.game) {
+                Ok(resolved) => group.lock().await.reconfigure(&resolved),
+                Err(err) => Err(err),
+            };
+            match result {
+                Ok(()) => debug!("Group <{group_id}> reconfigured"),
+                Err(err) => {
+                    error!("Invalid config for group <{group_id}>, keeping the current one: {err}")
+                }
+            }
+        }
+    }
 }
 
 /// Initialize group data for the bot.
@@ -125,23 +540,41 @@ async fn init_group_data(
     user_id: u64,
     default_config: RouletteConfig,
     groups: Vec<GroupConfig>,
-) -> HashMap<i64, Mutex<Roulette>> {
+    leave_unusable_chats: bool,
+    state: &State,
+) -> HashMap<i64, Mutex<Group>> {
     // Group-wise data (mapping group ID to Roulette instance)
     let mut group_data = HashMap::new();
+    // Groups configured but not activated, with the reason
+    let mut skipped = Vec::new();
     for group_config in groups {
         let group_id = group_config.id;
+        // Check config overrides
+        let resolved = match group_config.resolve(&default_config) {
+            Ok(resolved) => resolved,
+            Err(err) => {
+                error!("Invalid config for group <{group_id}>, ignoring: {err}");
+                skipped.push((group_id, "invalid config"));
+                continue;
+            }
+        };
         // Acquire chat info
         let get_chat_param = GetChatParams::builder().chat_id(group_id).build();
         let group = match bot.get_chat(&get_chat_param).await {
             Ok(res) => res.result,
             Err(err) => {
                 error!("Failed to get chat info for group <{group_id}>: {err}");
+                skipped.push((group_id, "chat not accessible"));
                 continue;
             }
         };
         // Check chat type
         if !matches!(group.type_field, ChatType::Supergroup) {
             info!("Group <{group_id}> is not a supergroup, ignoring");
+            if leave_unusable_chats {
+                leave_chat(bot, group_id).await;
+            }
+            skipped.push((group_id, "not a supergroup"));
             continue;
         }
         // Check permissions
@@ -153,6 +586,7 @@ async fn init_group_data(
             Ok(res) => res.result,
             Err(err) => {
                 error!("Failed to get chat member info for group <{group_id}>: {err}");
+                skipped.push((group_id, "membership unknown"));
                 continue;
             }
         };
@@ -163,21 +597,167 @@ async fn init_group_data(
         };
         if !can_restrict {
             error!("Bot cannot restrict members in group <{group_id}>, ignoring");
+            if leave_unusable_chats {
+                leave_chat(bot, group_id).await;
+            }
+            skipped.push((group_id, "cannot restrict members"));
             continue;
         }
 
+        // Check member count, which is only fetched if limited
+        let min_members = resolved.min_members();
+        if min_members > 0 {
+            let get_count_param = GetChatMemberCountParams::builder()
+                .chat_id(group_id)
+                .build();
+            let count = match bot.get_chat_member_count(&get_count_param).await {
+                Ok(res) => res.result,
+                Err(err) => {
+                    error!("Failed to get member count for group <{group_id}>: {err}");
+                    skipped.push((group_id, "member count unknown"));
+                    continue;
+                }
+            };
+            if !resolved.has_enough_members(count) {
+                info!(
+                    "Group <{group_id}> has {count} members, fewer than the minimum of {min_members}, ignoring"
+                );
+                skipped.push((group_id, "too few members"));
+                continue;
+            }
+        }
+
         // Start a new game for each group
-        let resolved = group_config.resolve(&default_config);
-        match resolved.start() {
-            Ok(game) => {
-                group_data.insert(group_id, Mutex::new(game));
+        let start_message = group_config.start_message(&resolved);
+        match Group::start(&resolved, &group_config.guns) {
+            Ok(mut group) => {
+                group.activate(Timestamp::now().as_second() as u64);
+                if let Some(group_state) = state.groups.get(&group_id)
+                    && let Err(err) = group.restore(group_state)
+                {
+                    info!("Discarded persisted revolvers of group <{group_id}>: {err}");
+                }
+                group_data.insert(group_id, Mutex::new(group));
                 debug!("Group <{group_id}> initialized");
+                if let Some(text) = start_message {
+                    announce_start(bot, group_id, text).await;
+                }
             }
             Err(err) => {
                 error!("Failed to start game for group <{group_id}>: {err}");
+                skipped.push((group_id, "game not started"));
             }
         }
     }
 
+    // Summarize the groups not activated, for diagnosing setup issues
+    if !skipped.is_empty() {
+        let list: Vec<_> = skipped
+            .iter()
+            .map(|(group_id, reason)| format!("<{group_id}> ({reason})"))
+            .collect();
+        warn!(
+            "{} configured groups were not activated: {}",
+            skipped.len(),
+            list.join(", ")
+        );
+    }
+
     group_data
 }
+
+/// Post the message telling that the game is live in the group.
+async fn announce_start(bot: &Bot, group_id: i64, text: String) {
+    let send_message_param = SendMessageParams::builder()
+        .chat_id(group_id)
+        .text(text)
+        .build();
+    if let Err(err) = bot.send_message(&send_message_param).await {
+        error!("Failed to post start message in group <{group_id}>: {err}");
+    }
+}
+
+/// Leave the given chat, where the bot cannot function.
+async fn leave_chat(bot: &Bot, chat_id: i64) {
+    let leave_param = LeaveChatParams::builder().chat_id(chat_id).build();
+    match bot.leave_chat(&leave_param).await {
+        Ok(_) => info!("Left unusable chat <{chat_id}>"),
+        Err(err) => error!("Failed to leave chat <{chat_id}>: {err}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Construct an update with the given ID.
+    fn update(update_id: u32) -> Update {
+        let json = format!(
+            r#"{{
+                "update_id": {update_id},
+                "message": {{
+                    "message_id": 2,
+                    "date": 0,
+                    "chat": {{"id": -100, "type": "supergroup"}},
+                    "text": "/roulette"
+                }}
+            }}"#
+        );
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_dispatch() {
+        let handled = Arc::new(AtomicUsize::new(0));
+        let handle = |_| {
+            let handled = handled.clone();
+            async move {
+                sleep(Duration::from_millis(50)).await;
+                handled.fetch_add(1, Ordering::SeqCst);
+            }
+        };
+
+        // Stops when the shutdown signal comes, even if updates never do
+        let mut tasks = JoinSet::new();
+        let mut batches = vec![vec![update(1), update(2)]];
+        let next = async || match batches.pop() {
+            Some(batch) => Some(batch),
+            None => std::future::pending().await,
+        };
+        dispatch(&mut tasks, sleep(Duration::from_millis(10)), next, handle).await;
+        // The in-flight updates are left for the caller to wait for
+        assert_eq!(tasks.len(), 2);
+        while tasks.join_next().await.is_some() {}
+        assert_eq!(handled.load(Ordering::SeqCst), 2);
+
+        // Stops when no more updates come
+        let mut tasks = JoinSet::new();
+        let mut batches = vec![vec![update(3)]];
+        let next = async || batches.pop();
+        dispatch(&mut tasks, std::future::pending(), next, handle).await;
+        while tasks.join_next().await.is_some() {}
+        assert_eq!(handled.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_shared_group_data() {
+        let game = RouletteConfig::default().start().unwrap();
+        let group_data = Arc::new(HashMap::from([(-100, Mutex::new(Group::new(game)))]));
+        let mut tasks = JoinSet::new();
+        for user_id in 1..=3 {
+            let group_data = group_data.clone();
+            tasks.spawn(async move {
+                group_data[&-100]
+                    .lock()
+                    .await
+                    .record_outcome(user_id, "Ferris", false);
+            });
+        }
+        while tasks.join_next().await.is_some() {}
+
+        // The changes made by the tasks are shared, and the map is freed with the last reference
+        let group_data = Arc::into_inner(group_data).unwrap();
+        assert_eq!(group_data[&-100].lock().await.user_stats().len(), 3);
+    }
+}
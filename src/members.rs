@@ -0,0 +1,48 @@
+//! Periodic check of the member count of groups with `min_members`.
+
+use super::Group;
+use frankenstein::{AsyncTelegramApi, client_reqwest::Bot, methods::GetChatMemberCountParams};
+use log::{error, info};
+use std::{collections::HashMap, time::Duration};
+use tokio::{sync::Mutex, time::interval};
+
+/// How often to check the member count.
+const CHECK_PERIOD: Duration = Duration::from_secs(15 * 60);
+
+/// Pause the groups that have fallen below `min_members`, and resume those paused so once they
+/// have enough members again, never returning.
+pub async fn run(bot: &Bot, group_data: &HashMap<i64, Mutex<Group>>) {
+    let mut interval = interval(CHECK_PERIOD);
+    // Checked on startup already
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        for (&group_id, group) in group_data {
+            let min_members = group.lock().await.roulette().config().min_members();
+            if min_members == 0 {
+                continue;
+            }
+            let get_count_param = GetChatMemberCountParams::builder()
+                .chat_id(group_id)
+                .build();
+            let count = match bot.get_chat_member_count(&get_count_param).await {
+                Ok(res) => res.result,
+                Err(err) => {
+                    error!("Failed to get member count for group <{group_id}>: {err}");
+                    continue;
+                }
+            };
+            let mut group = group.lock().await;
+            let enough = group.roulette().config().has_enough_members(count);
+            if group.check_members(enough) {
+                if enough {
+                    info!("Group <{group_id}> has {count} members again, resuming the game");
+                } else {
+                    info!(
+                        "Group <{group_id}> has {count} members, fewer than the minimum of {min_members}, pausing the game"
+                    );
+                }
+            }
+        }
+    }
+}
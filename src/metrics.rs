@@ -0,0 +1,79 @@
+//! Metrics of the games, in the Prometheus text format.
+
+use super::{Group, Stats};
+use std::{collections::HashMap, fmt::Write};
+use tokio::sync::Mutex;
+
+/// Content type of the Prometheus text format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// A counter, as the name, help text and the field of the statistics.
+type Counter = (&'static str, &'static str, fn(&Stats) -> u64);
+
+/// Counters exported.
+const COUNTERS: [Counter; 4] = [
+    (
+        "roulette_fires_total",
+        "Number of pulls of the trigger.",
+        |stats| stats.fires,
+    ),
+    (
+        "roulette_bullets_total",
+        "Number of bullets fired.",
+        |stats| stats.bullets,
+    ),
+    ("roulette_jams_total", "Number of jams.", |stats| stats.jams),
+    ("roulette_reloads_total", "Number of reloads.", |stats| {
+        stats.reloads
+    }),
+];
+
+/// Render the counters of all groups, labelled by the chat ID.
+pub async fn render(group_data: &HashMap<i64, Mutex<Group>>) -> String {
+    let mut stats = Vec::with_capacity(group_data.len());
+    for (&chat_id, group) in group_data {
+        stats.push((chat_id, group.lock().await.stats()));
+    }
+    stats.sort_unstable_by_key(|&(chat_id, _)| chat_id);
+    format_counters(&stats)
+}
+
+/// Format the counters of the given statistics of each chat.
+fn format_counters(stats: &[(i64, Stats)]) -> String {
+    let mut text = String::new();
+    for (name, help, value) in COUNTERS {
+        // Writing to a string never fails
+        let _ = writeln!(text, "# HELP {name} {help}");
+        let _ = writeln!(text, "# TYPE {name} counter");
+        for (chat_id, stats) in stats {
+            let _ = writeln!(text, "{name}{{chat=\"{chat_id}\"}} {}", value(stats));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RouletteConfig;
+
+    #[tokio::test]
+    async fn test_render() {
+        let config = RouletteConfig {
+            jam_probability: 0.0,
+            ..Default::default()
+        };
+        let group = Group::new(config.start().unwrap());
+        let group_data = HashMap::from([(-100, Mutex::new(group))]);
+        let text = render(&group_data).await;
+        assert!(text.contains("# TYPE roulette_fires_total counter\n"));
+        assert!(text.contains("roulette_fires_total{chat=\"-100\"} 0\n"));
+
+        // Firing is counted right away
+        group_data[&-100].lock().await.roulette_mut().fire();
+        let text = render(&group_data).await;
+        assert!(text.contains("roulette_fires_total{chat=\"-100\"} 1\n"));
+        assert!(text.contains("roulette_jams_total{chat=\"-100\"} 0\n"));
+        assert_eq!(text.lines().count(), 4 * 3);
+    }
+}
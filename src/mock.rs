@@ -0,0 +1,161 @@
+//! Mock of the Telegram API, for testing commands and the handling of updates end-to-end.
+//!
+//! [`MockApi`] implements [`Api`](crate::Api) like the real bot, so it can stand in for it in the
+//! [`Context`] that commands are executed in. Give it a canned result for each method the test
+//! expects, then inspect the requests it recorded. Commands are usually sent by [`SENDER`] with
+//! [`message`], and executed in a [`context`] around the mock.
+
+use crate::{ratelimit::RateLimiter, update::Context};
+use frankenstein::{AsyncTelegramApi, Error, response::ErrorResponse, types::Message};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+/// ID of the user sending the messages of [`message`].
+pub const SENDER: u64 = 3;
+
+/// Construct a message with the text, sent by [`SENDER`] in the supergroup `-100`.
+pub fn message(text: &str) -> Message {
+    serde_json::from_value(json!({
+        "message_id": 1,
+        "date": 0,
+        "chat": {"id": -100, "type": "supergroup"},
+        "from": {"id": SENDER, "is_bot": false, "first_name": "Ferris"},
+        "text": text
+    }))
+    .unwrap()
+}
+
+/// Construct a context talking to the API under the default triggers, without whitelisting any
+/// group, for executing commands directly.
+pub fn context<B>(bot: B) -> Context<B> {
+    Context {
+        bot: Arc::new(bot),
+        username: "roulette_bot".to_string(),
+        triggers: Default::default(),
+        group_data: Default::default(),
+        state_writer: None,
+        reply_ttl_secs: None,
+        process_edits: false,
+        private_reply: "Groups only".to_string(),
+        limiter: RateLimiter::default(),
+        dry_run: false,
+    }
+}
+
+/// Mock of the Telegram API, answering each method with a canned result and recording the requests.
+///
+/// Methods without a canned result fail with an API error, as do those set up with [`MockApi::failing`].
+#[derive(Debug, Default)]
+pub struct MockApi {
+    /// Mapping method name, like `sendMessage`, to its result.
+    results: HashMap<&'static str, Value>,
+    /// Mapping method name to the error code and description it fails with.
+    failures: HashMap<&'static str, (u64, String)>,
+    /// Requests received so far, as the method name and the parameters.
+    requests: Mutex<Vec<(String, Value)>>,
+}
+
+impl MockApi {
+    /// Answer the method with the given result.
+    pub fn with(mut self, method: &'static str, result: Value) -> Self {
+        self.results.insert(method, result);
+        self
+    }
+
+    /// Fail the method with the given API error, like Telegram rejecting the request.
+    pub fn failing(mut self, method: &'static str, error_code: u64, description: &str) -> Self {
+        self.failures
+            .insert(method, (error_code, description.to_string()));
+        self
+    }
+
+    /// Answer `getChatMember` with [`SENDER`] holding the status, like `member` or `creator`.
+    pub fn with_member(self, status: &str) -> Self {
+        let user = json!({"id": SENDER, "is_bot": false, "first_name": "Ferris"});
+        let member = json!({"status": status, "user": user, "is_anonymous": false});
+        self.with("getChatMember", member)
+    }
+
+    /// Answer `sendMessage` with a message sent to the supergroup `-100`.
+    pub fn with_sending(self) -> Self {
+        let sent = json!({
+            "message_id": 4,
+            "date": 0,
+            "chat": {"id": -100, "type": "supergroup"}
+        });
+        self.with("sendMessage", sent)
+    }
+
+    /// Get the names of the methods requested so far, in order.
+    pub fn methods(&self) -> Vec<String> {
+        let requests = self.requests.lock().unwrap();
+        requests.iter().map(|(method, _)| method.clone()).collect()
+    }
+
+    /// Get the parameters of the requests to the method so far, in order.
+    pub fn requests(&self, method: &str) -> Vec<Value> {
+        let requests = self.requests.lock().unwrap();
+        requests
+            .iter()
+            .filter(|(name, _)| name == method)
+            .map(|(_, params)| params.clone())
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncTelegramApi for MockApi {
+    type Error = Error;
+
+    async fn request<Params, Output>(
+        &self,
+        method: &str,
+        params: Option<Params>,
+    ) -> Result<Output, Self::Error>
+    where
+        Params: Serialize + Debug + Send,
+        Output: DeserializeOwned,
+    {
+        let params = params.map_or(Value::Null, |params| serde_json::to_value(params).unwrap());
+        self.requests
+            .lock()
+            .unwrap()
+            .push((method.to_string(), params));
+        if let Some((error_code, description)) = self.failures.get(method) {
+            return Err(Error::Api(ErrorResponse {
+                ok: false,
+                description: description.clone(),
+                error_code: *error_code,
+                parameters: None,
+            }));
+        }
+        let Some(result) = self.results.get(method) else {
+            return Err(Error::Api(ErrorResponse {
+                ok: false,
+                description: format!("Not mocked: {method}"),
+                error_code: 404,
+                parameters: None,
+            }));
+        };
+        Ok(serde_json::from_value(json!({"ok": true, "result": result})).unwrap())
+    }
+
+    async fn request_with_form_data<Params, Output>(
+        &self,
+        method: &str,
+        params: Params,
+        _files: Vec<(&str, PathBuf)>,
+    ) -> Result<Output, Self::Error>
+    where
+        Params: Serialize + Debug + Send,
+        Output: DeserializeOwned,
+    {
+        self.request(method, Some(params)).await
+    }
+}
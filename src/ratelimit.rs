@@ -0,0 +1,130 @@
+//! Rate limiting of outbound messages, staying within the limits of Telegram.
+
+use super::constants::{CHAT_BURST, CHAT_INTERVAL};
+use frankenstein::Error;
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Token buckets of each chat, delaying messages beyond the rate instead of dropping them.
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Number of messages that can be sent at once.
+    burst: f64,
+    /// Interval between refilling tokens.
+    interval: Duration,
+    /// Mapping chat ID to its bucket.
+    buckets: Mutex<HashMap<i64, Bucket>>,
+}
+
+/// Token bucket of a chat.
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    /// Tokens left, negative if messages are already waiting for tokens.
+    tokens: f64,
+    /// When the tokens were last refilled.
+    refilled: Instant,
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing `burst` messages at once per chat, and one more per `interval`.
+    pub fn new(burst: u32, interval: Duration) -> Self {
+        Self {
+            burst: f64::from(burst),
+            interval,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a token for sending a message to the chat, returning how long to wait before sending.
+    pub fn acquire(&self, chat_id: i64) -> Duration {
+        self.acquire_at(chat_id, Instant::now())
+    }
+
+    /// Take a token for sending a message to the chat at `now`, returning how long to wait before sending.
+    fn acquire_at(&self, chat_id: i64, now: Instant) -> Duration {
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(chat_id).or_insert(Bucket {
+            tokens: self.burst,
+            refilled: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.refilled);
+        bucket.tokens = (bucket.tokens + elapsed.div_duration_f64(self.interval)).min(self.burst);
+        bucket.refilled = now;
+        // Reserve the token even if not yet available, so that waiting messages keep their order
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            self.interval.mul_f64(-bucket.tokens)
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(CHAT_BURST, CHAT_INTERVAL)
+    }
+}
+
+/// Get how long Telegram asks to wait before retrying, if the request was rejected for flooding.
+pub fn retry_after(err: &Error) -> Option<Duration> {
+    let Error::Api(response) = err else {
+        return None;
+    };
+    if response.error_code != 429 {
+        return None;
+    }
+    let secs = response.parameters?.retry_after?;
+    Some(Duration::from_secs(secs.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frankenstein::response::{ErrorResponse, ResponseParameters};
+
+    #[test]
+    fn test_acquire() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(3));
+        let start = Instant::now();
+        // The burst goes through, the rest waits in order
+        assert_eq!(limiter.acquire_at(1, start), Duration::ZERO);
+        assert_eq!(limiter.acquire_at(1, start), Duration::ZERO);
+        assert_eq!(limiter.acquire_at(1, start), Duration::from_secs(3));
+        assert_eq!(limiter.acquire_at(1, start), Duration::from_secs(6));
+        // Other chats are not affected
+        assert_eq!(limiter.acquire_at(2, start), Duration::ZERO);
+        // Refilled over time, up to the burst
+        let later = start + Duration::from_secs(9);
+        assert_eq!(limiter.acquire_at(1, later), Duration::ZERO);
+        assert_eq!(limiter.acquire_at(1, later), Duration::from_secs(3));
+        let much_later = start + Duration::from_secs(600);
+        assert_eq!(limiter.acquire_at(2, much_later), Duration::ZERO);
+        assert_eq!(limiter.acquire_at(2, much_later), Duration::ZERO);
+        assert_eq!(limiter.acquire_at(2, much_later), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let api_error = |error_code, retry_after| {
+            Error::Api(ErrorResponse {
+                ok: false,
+                description: "Too Many Requests: retry after 5".to_string(),
+                error_code,
+                parameters: Some(ResponseParameters {
+                    migrate_to_chat_id: None,
+                    retry_after,
+                }),
+            })
+        };
+        assert_eq!(
+            retry_after(&api_error(429, Some(5))),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(retry_after(&api_error(429, None)), None);
+        assert_eq!(retry_after(&api_error(400, Some(5))), None);
+    }
+}
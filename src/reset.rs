@@ -0,0 +1,65 @@
+//! Daily reset of per-user stats and cooldowns.
+
+use super::Group;
+use jiff::Timestamp;
+use log::info;
+use std::{collections::HashMap, time::Duration};
+use tokio::{sync::Mutex, time::interval};
+
+/// Seconds in a day.
+pub(crate) const DAY_SECS: u64 = 24 * 60 * 60;
+/// How often to check whether the reset is due.
+const CHECK_PERIOD: Duration = Duration::from_secs(60);
+
+/// Whether the daily reset at `hour` UTC is due at the UNIX timestamp `now`, having last checked at `last`.
+pub fn should_reset(last: u64, now: u64, hour: u8) -> bool {
+    crossed(last, now, u64::from(hour) * 3600, DAY_SECS)
+}
+
+/// Whether a time recurring every `period` seconds, `offset` seconds into each period since the
+/// epoch, lies after the UNIX timestamp `last` and no later than `now`.
+pub(crate) fn crossed(last: u64, now: u64, offset: u64, period: u64) -> bool {
+    // Periods since the epoch, starting at the offset
+    let index = |time: u64| (time + period - offset) / period;
+    index(now) > index(last)
+}
+
+/// Reset the per-user stats and cooldowns of all groups daily at `hour` UTC, never returning.
+pub async fn run(group_data: &HashMap<i64, Mutex<Group>>, hour: u8) {
+    let mut interval = interval(CHECK_PERIOD);
+    let mut last = Timestamp::now().as_second() as u64;
+    loop {
+        interval.tick().await;
+        let now = Timestamp::now().as_second() as u64;
+        if should_reset(last, now, hour) {
+            for group in group_data.values() {
+                group.lock().await.reset_users();
+            }
+            info!("Reset per-user stats and cooldowns");
+        }
+        last = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_reset() {
+        let hour = |h: u64| h * 3600;
+        // At midnight
+        assert!(should_reset(hour(23), hour(24), 0));
+        assert!(!should_reset(hour(24), hour(24) + 60, 0));
+        assert!(!should_reset(hour(1), hour(23), 0));
+        // At 6 o'clock
+        assert!(should_reset(hour(5), hour(6), 6));
+        assert!(!should_reset(hour(6), hour(7), 6));
+        assert!(!should_reset(hour(7), hour(24 + 5), 6));
+        assert!(should_reset(hour(7), hour(24 + 6), 6));
+        // Missed checks still reset once
+        assert!(should_reset(hour(5), hour(48 + 7), 6));
+        // Before the first reset hour after the epoch
+        assert!(!should_reset(0, hour(5), 6));
+    }
+}
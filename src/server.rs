@@ -0,0 +1,303 @@
+//! Optional HTTP servers for health checks, webhooks and metrics.
+
+use super::{Group, metrics};
+use frankenstein::updates::Update;
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use hyper::{
+    Method, Request, Response, StatusCode,
+    body::{Body, Bytes, Incoming},
+    header::{CONTENT_TYPE, HeaderValue},
+    server::conn::http1,
+    service::service_fn,
+};
+use hyper_util::rt::TokioIo;
+use log::{debug, info};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    error::Error,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::{
+    net::TcpListener,
+    sync::mpsc::{Sender, error::TrySendError},
+};
+
+/// Header carrying the secret token of webhook requests.
+const SECRET_HEADER: &str = "X-Telegram-Bot-Api-Secret-Token";
+
+/// Maximum size of a webhook request body in bytes, far above that of any update.
+const MAX_BODY_BYTES: usize = 1 << 20;
+
+/// Number of received updates that may wait for the update loop, beyond which Telegram is asked to retry later.
+pub const WEBHOOK_QUEUE: usize = 256;
+
+/// Health of the update loop.
+#[derive(Debug)]
+pub struct Health {
+    /// When the last successful poll happened.
+    last_poll_ok: Mutex<Instant>,
+    /// Maximum duration since the last successful poll to be considered alive.
+    threshold: Duration,
+}
+
+impl Health {
+    /// Create a new health tracker, treating startup as a successful poll.
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            last_poll_ok: Mutex::new(Instant::now()),
+            threshold,
+        }
+    }
+
+    /// Record a successful poll.
+    pub fn record_poll(&self) {
+        *self.last_poll_ok.lock().unwrap() = Instant::now();
+    }
+
+    /// Whether the last successful poll is within the threshold.
+    pub fn is_alive(&self) -> bool {
+        self.last_poll_ok.lock().unwrap().elapsed() <= self.threshold
+    }
+}
+
+/// Receiver of updates pushed by Telegram.
+#[derive(Debug)]
+pub struct Webhook {
+    /// Path to receive updates at.
+    pub path: String,
+    /// Secret token that requests must carry, if any.
+    pub secret: Option<String>,
+    /// Channel to forward the received updates to, holding up to [`WEBHOOK_QUEUE`] of them.
+    pub sender: Sender<Update>,
+}
+
+impl Webhook {
+    /// Accept the update in the request body if it carries the secret token, returning the response status.
+    fn receive(&self, secret: Option<&[u8]>, body: &[u8]) -> StatusCode {
+        if let Some(expected) = &self.secret
+            && secret != Some(expected.as_bytes())
+        {
+            return StatusCode::UNAUTHORIZED;
+        }
+        let update = match serde_json::from_slice(body) {
+            Ok(update) => update,
+            Err(err) => {
+                debug!("Failed to parse webhook update: {err}");
+                return StatusCode::BAD_REQUEST;
+            }
+        };
+        match self.sender.try_send(update) {
+            Ok(()) => StatusCode::OK,
+            // The update loop is behind or has stopped, so let Telegram retry later
+            Err(TrySendError::Full(_) | TrySendError::Closed(_)) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+/// Serve the HTTP endpoints on the given address:
+///
+/// - `/healthz`: `200` if the update loop is alive, `503` otherwise.
+/// - The path of the webhook, if any: accepts updates `POST`ed by Telegram.
+pub async fn serve(
+    addr: SocketAddr,
+    health: Arc<Health>,
+    webhook: Option<Arc<Webhook>>,
+) -> std::io::Result<()> {
+    listen("HTTP server", addr, move |req| {
+        let health = health.clone();
+        let webhook = webhook.clone();
+        async move { handle(req, &health, webhook.as_deref()).await }
+    })
+    .await
+}
+
+/// Serve the metrics of all groups in the Prometheus text format on the given address, at `/metrics`.
+pub async fn serve_metrics(
+    addr: SocketAddr,
+    group_data: Arc<HashMap<i64, tokio::sync::Mutex<Group>>>,
+) -> std::io::Result<()> {
+    listen("Metrics exporter", addr, move |req| {
+        let group_data = group_data.clone();
+        async move {
+            if req.uri().path() != "/metrics" {
+                let mut response = Response::new(Full::new(Bytes::from("Not found")));
+                *response.status_mut() = StatusCode::NOT_FOUND;
+                return response;
+            }
+            let body = metrics::render(&group_data).await;
+            let mut response = Response::new(Full::new(Bytes::from(body)));
+            response.headers_mut().insert(
+                CONTENT_TYPE,
+                HeaderValue::from_static(metrics::CONTENT_TYPE),
+            );
+            response
+        }
+    })
+    .await
+}
+
+/// Accept connections on the given address, answering each request with the handler.
+async fn listen<F, Fut>(name: &str, addr: SocketAddr, handler: F) -> std::io::Result<()>
+where
+    F: Fn(Request<Incoming>) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = Response<Full<Bytes>>> + Send,
+{
+    let listener = TcpListener::bind(addr).await?;
+    info!("{name} listening on {addr}");
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let handler = handler.clone();
+        tokio::spawn(async move {
+            let service = service_fn(|req| {
+                let response = handler(req);
+                async move { Ok::<_, Infallible>(response.await) }
+            });
+            if let Err(err) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                debug!("Failed to serve HTTP connection: {err}");
+            }
+        });
+    }
+}
+
+/// Handle the request, passing updates to the webhook and routing others by the path.
+async fn handle<B>(
+    req: Request<B>,
+    health: &Health,
+    webhook: Option<&Webhook>,
+) -> Response<Full<Bytes>>
+where
+    B: Body,
+    B::Error: Into<Box<dyn Error + Send + Sync>>,
+{
+    let Some(webhook) =
+        webhook.filter(|webhook| req.method() == Method::POST && req.uri().path() == webhook.path)
+    else {
+        return route(req.uri().path(), health);
+    };
+    let secret = req.headers().get(SECRET_HEADER).cloned();
+    let status = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(body) => webhook.receive(secret.as_ref().map(|s| s.as_bytes()), &body.to_bytes()),
+        Err(err) if err.is::<LengthLimitError>() => StatusCode::PAYLOAD_TOO_LARGE,
+        Err(err) => {
+            debug!("Failed to read webhook request: {err}");
+            StatusCode::BAD_REQUEST
+        }
+    };
+    let mut response = Response::new(Full::new(Bytes::new()));
+    *response.status_mut() = status;
+    response
+}
+
+/// Route the request by its path.
+fn route(path: &str, health: &Health) -> Response<Full<Bytes>> {
+    let (status, body) = match path {
+        "/healthz" if health.is_alive() => (StatusCode::OK, "OK"),
+        "/healthz" => (StatusCode::SERVICE_UNAVAILABLE, "Update loop stalled"),
+        _ => (StatusCode::NOT_FOUND, "Not found"),
+    };
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_healthz() {
+        let health = Health::new(Duration::from_secs(60));
+        assert_eq!(route("/healthz", &health).status(), StatusCode::OK);
+        assert_eq!(route("/other", &health).status(), StatusCode::NOT_FOUND);
+
+        let stalled = Health::new(Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(
+            route("/healthz", &stalled).status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    /// Body of a webhook request carrying a `/roulette` update.
+    const UPDATE: &[u8] = br#"{"update_id": 1, "message": {"message_id": 2, "date": 0, "chat": {"id": -100, "type": "supergroup"}, "text": "/roulette"}}"#;
+
+    #[test]
+    fn test_webhook() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+        let webhook = Webhook {
+            path: "/webhook".to_string(),
+            secret: Some("secret".to_string()),
+            sender,
+        };
+        let body = UPDATE;
+
+        assert_eq!(webhook.receive(Some(b"secret"), body), StatusCode::OK);
+        assert_eq!(receiver.try_recv().unwrap().update_id, 1);
+        assert_eq!(webhook.receive(None, body), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            webhook.receive(Some(b"wrong"), body),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            webhook.receive(Some(b"secret"), b"{}"),
+            StatusCode::BAD_REQUEST
+        );
+        assert!(receiver.try_recv().is_err());
+
+        // Turned away once the queue is full
+        assert_eq!(webhook.receive(Some(b"secret"), body), StatusCode::OK);
+        assert_eq!(
+            webhook.receive(Some(b"secret"), body),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        drop(receiver);
+        assert_eq!(
+            webhook.receive(Some(b"secret"), body),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(WEBHOOK_QUEUE);
+        let webhook = Webhook {
+            path: "/webhook".to_string(),
+            secret: None,
+            sender,
+        };
+        let health = Health::new(Duration::from_secs(60));
+        let request = |method, path, body: Vec<u8>| {
+            Request::builder()
+                .method(method)
+                .uri(path)
+                .body(Full::new(Bytes::from(body)))
+                .unwrap()
+        };
+        let status = async |req| handle(req, &health, Some(&webhook)).await.status();
+
+        // Updates are posted to the webhook path only
+        let req = request(Method::POST, "/webhook", UPDATE.to_vec());
+        assert_eq!(status(req).await, StatusCode::OK);
+        assert_eq!(receiver.try_recv().unwrap().update_id, 1);
+        let req = request(Method::GET, "/webhook", UPDATE.to_vec());
+        assert_eq!(status(req).await, StatusCode::NOT_FOUND);
+        let req = request(Method::POST, "/other", UPDATE.to_vec());
+        assert_eq!(status(req).await, StatusCode::NOT_FOUND);
+        let req = request(Method::GET, "/healthz", Vec::new());
+        assert_eq!(status(req).await, StatusCode::OK);
+
+        // Oversized bodies are refused without being parsed
+        let req = request(Method::POST, "/webhook", vec![b' '; MAX_BODY_BYTES + 1]);
+        assert_eq!(status(req).await, StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(receiver.try_recv().is_err());
+    }
+}
@@ -0,0 +1,247 @@
+//! Persistence of group states across restarts.
+
+use super::{Group, Stats};
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use tokio::{sync::Mutex, time::interval};
+
+/// Persisted state of all groups.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct State {
+    /// Mapping group ID to its state.
+    #[serde(default)]
+    pub groups: HashMap<i64, GroupState>,
+}
+
+/// Persisted state of a group.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupState {
+    /// Whether the game is enabled.
+    pub enabled: bool,
+    /// The loading of the revolver, absent in state files of older versions.
+    #[serde(default)]
+    pub roulette: Option<RouletteState>,
+    /// Mapping name to the loading of each additional gun, absent in state files of older versions.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub guns: HashMap<String, RouletteState>,
+    /// Mapping topic ID to the state of its revolvers with `per_topic`, absent in state files of older versions.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub topics: HashMap<i32, TopicState>,
+}
+
+/// Persisted revolvers of a topic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopicState {
+    /// The loading of the default gun.
+    pub roulette: RouletteState,
+    /// Mapping name to the loading of each additional gun.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub guns: HashMap<String, RouletteState>,
+}
+
+/// Persisted loading of a revolver.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouletteState {
+    /// Whether each chamber is loaded with a bullet.
+    pub contents: Vec<bool>,
+    /// Position of the next chamber to fire.
+    pub position: usize,
+    /// Seed of the loading.
+    pub seed: u64,
+    /// Position in the random number stream of the loading, absent in state files of older versions, which replay it from the start.
+    #[serde(default)]
+    pub word_pos: u128,
+    /// Statistics of the game, absent in state files of older versions.
+    #[serde(default)]
+    pub stats: Stats,
+    /// Number of bullets hit in the loading, absent in state files of older versions.
+    #[serde(default)]
+    pub bullets_hit: usize,
+}
+
+impl State {
+    /// Load the state from the given file, defaulting to an empty state if it does not exist.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| e.to_string()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    /// Capture the state of all groups.
+    pub async fn capture(group_data: &HashMap<i64, Mutex<Group>>) -> Self {
+        let mut groups = HashMap::with_capacity(group_data.len());
+        for (id, group) in group_data {
+            groups.insert(*id, group.lock().await.state());
+        }
+        Self { groups }
+    }
+
+    /// Save the state to the given file atomically, by writing to a temporary file and renaming it.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, content).map_err(|e| e.to_string())?;
+        fs::rename(&temp, path).map_err(|e| e.to_string())
+    }
+}
+
+/// Debounced writer of the state file.
+///
+/// Changes are only marked in memory, and flushed to the file periodically by [`StateWriter::run`], so that busy groups don't cause heavy I/O. Thus a crash loses at most one interval of changes, and a final [`StateWriter::flush`] should be done on shutdown.
+#[derive(Debug)]
+pub struct StateWriter {
+    /// Path to the state file.
+    path: PathBuf,
+    /// Whether there are changes not yet flushed.
+    dirty: AtomicBool,
+    /// Serializes concurrent flushes.
+    lock: Mutex<()>,
+}
+
+impl StateWriter {
+    /// Create a writer of the given state file.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            dirty: AtomicBool::new(false),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Mark the state as changed, to be flushed later.
+    pub fn mark_dirty(&self) {
+        self.dirty.store(true, Ordering::Release);
+    }
+
+    /// Flush the state to the file if it has changed, returning whether it was written.
+    pub async fn flush(&self, group_data: &HashMap<i64, Mutex<Group>>) -> Result<bool, String> {
+        let _guard = self.lock.lock().await;
+        if !self.dirty.swap(false, Ordering::AcqRel) {
+            return Ok(false);
+        }
+        let state = State::capture(group_data).await;
+        state.save(&self.path).inspect_err(|_| {
+            // Retry on next flush
+            self.mark_dirty();
+        })?;
+        Ok(true)
+    }
+
+    /// Flush the state periodically, never returning.
+    pub async fn run(&self, group_data: &HashMap<i64, Mutex<Group>>, period: Duration) {
+        let mut interval = interval(period);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.flush(group_data).await {
+                error!("Failed to save state file ({}): {err}", self.path.display());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let path = std::env::temp_dir().join("rustacean-roulette-test-state.json");
+        let mut state = State::default();
+        state.groups.insert(
+            -100,
+            GroupState {
+                enabled: false,
+                roulette: Some(RouletteState {
+                    contents: vec![true, false],
+                    position: 1,
+                    seed: 42,
+                    word_pos: 1 << 70,
+                    stats: Stats {
+                        fires: 3,
+                        bullets: 1,
+                        jams: 1,
+                        reloads: 1,
+                        mute_secs: 120,
+                    },
+                    bullets_hit: 1,
+                }),
+                guns: HashMap::from([(
+                    "left".to_string(),
+                    RouletteState {
+                        contents: vec![false],
+                        position: 0,
+                        seed: 7,
+                        word_pos: 0,
+                        stats: Stats::default(),
+                        bullets_hit: 0,
+                    },
+                )]),
+                topics: HashMap::from([(
+                    5,
+                    TopicState {
+                        roulette: RouletteState {
+                            contents: vec![true],
+                            position: 0,
+                            seed: 9,
+                            word_pos: 0,
+                            stats: Stats::default(),
+                            bullets_hit: 0,
+                        },
+                        guns: HashMap::new(),
+                    },
+                )]),
+            },
+        );
+        state.groups.insert(
+            -200,
+            GroupState {
+                enabled: true,
+                roulette: None,
+                guns: HashMap::new(),
+                topics: HashMap::new(),
+            },
+        );
+        state.save(&path).unwrap();
+
+        let loaded = State::load(&path).unwrap();
+        assert_eq!(loaded.groups, state.groups);
+        fs::remove_file(&path).unwrap();
+        assert!(State::load(&path).unwrap().groups.is_empty());
+    }
+
+    #[test]
+    fn test_legacy() {
+        let state: State =
+            serde_json::from_str(r#"{"groups": {"-100": {"enabled": false}}}"#).unwrap();
+        assert_eq!(state.groups[&-100].roulette, None);
+        assert!(state.groups[&-100].guns.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_writer() {
+        let path = std::env::temp_dir().join("rustacean-roulette-test-writer.json");
+        let writer = StateWriter::new(path.clone());
+        let mut group_data = HashMap::new();
+        let group = Group::new(crate::RouletteConfig::default().start().unwrap());
+        group_data.insert(-100, Mutex::new(group));
+
+        // Only written when dirty
+        assert_eq!(writer.flush(&group_data).await, Ok(false));
+        assert!(!path.exists());
+        writer.mark_dirty();
+        assert_eq!(writer.flush(&group_data).await, Ok(true));
+        assert_eq!(writer.flush(&group_data).await, Ok(false));
+        assert!(State::load(&path).unwrap().groups[&-100].enabled);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,162 @@
+//! Tournaments of multiple rounds, declaring a champion.
+
+use super::{FireResult, Roulette};
+use std::time::{Duration, Instant};
+
+/// Maximum number of rounds before a tournament ends in a draw, e.g. when the gun always jams.
+const MAX_ROUNDS: usize = 100;
+
+/// A tournament, accepting participants until the join window closes.
+#[derive(Clone, Debug)]
+pub struct Tournament {
+    /// Participants in order of joining, as user ID and display name.
+    participants: Vec<(u64, String)>,
+    /// When the join window closes.
+    join_until: Instant,
+}
+
+/// Error when joining a tournament.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinError {
+    /// The join window has closed.
+    Closed,
+    /// The user has already joined.
+    AlreadyJoined,
+}
+
+/// Outcome of a tournament.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TournamentResult {
+    /// Participants eliminated in each round, as user ID and display name.
+    pub rounds: Vec<Vec<(u64, String)>>,
+    /// The champion, or `None` if nobody joined or the tournament ended in a draw.
+    pub champion: Option<(u64, String)>,
+}
+
+impl Tournament {
+    /// Start a tournament, accepting participants within the given window.
+    pub fn new(join_window: Duration) -> Self {
+        Self {
+            participants: Vec::new(),
+            join_until: Instant::now() + join_window,
+        }
+    }
+
+    /// Whether the join window is still open.
+    pub fn is_open(&self) -> bool {
+        Instant::now() < self.join_until
+    }
+
+    /// Time left until the join window closes.
+    pub fn time_left(&self) -> Duration {
+        self.join_until.saturating_duration_since(Instant::now())
+    }
+
+    /// Participants in order of joining.
+    pub fn participants(&self) -> &[(u64, String)] {
+        &self.participants
+    }
+
+    /// Join the tournament, returning the number of participants.
+    pub fn join(&mut self, user_id: u64, name: String) -> Result<usize, JoinError> {
+        if !self.is_open() {
+            return Err(JoinError::Closed);
+        }
+        if self.participants.iter().any(|(id, _)| *id == user_id) {
+            return Err(JoinError::AlreadyJoined);
+        }
+        self.participants.push((user_id, name));
+        Ok(self.participants.len())
+    }
+
+    /// Run the tournament with the given gun.
+    ///
+    /// In each round, remaining participants take turns to fire, and those who draw a bullet are eliminated. The round ends early once a single participant remains, who is the champion. The gun is reloaded whenever it runs out of bullets, and jams count as surviving.
+    pub fn run(self, gun: &mut Roulette) -> TournamentResult {
+        let mut remaining = self.participants;
+        let mut rounds = Vec::new();
+        while remaining.len() > 1 && rounds.len() < MAX_ROUNDS {
+            let mut eliminated = Vec::new();
+            let mut index = 0;
+            while index < remaining.len() && remaining.len() > 1 {
+                let result = match gun.fire() {
+                    FireResult::NoBullets => {
+                        gun.reload();
+                        gun.fire()
+                    }
+                    result => result,
+                };
+                if gun.is_empty() {
+                    gun.reload();
+                }
+                if result.is_bullet() {
+                    eliminated.push(remaining.remove(index));
+                } else {
+                    index += 1;
+                }
+            }
+            rounds.push(eliminated);
+        }
+
+        let champion = if remaining.len() == 1 {
+            remaining.pop()
+        } else {
+            None
+        };
+        TournamentResult { rounds, champion }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RouletteConfig;
+
+    fn tournament(players: u64) -> Tournament {
+        let mut tournament = Tournament::new(Duration::from_secs(60));
+        for id in 0..players {
+            tournament.join(id, format!("Player {id}")).unwrap();
+        }
+        tournament
+    }
+
+    #[test]
+    fn test_join() {
+        let mut tournament = tournament(2);
+        assert_eq!(
+            tournament.join(0, "Again".to_string()),
+            Err(JoinError::AlreadyJoined)
+        );
+        assert_eq!(tournament.join(2, "Player 2".to_string()), Ok(3));
+
+        let mut closed = Tournament::new(Duration::ZERO);
+        assert_eq!(closed.join(0, "Late".to_string()), Err(JoinError::Closed));
+    }
+
+    #[test]
+    fn test_run() {
+        for players in [0, 1, 2, 5] {
+            let mut gun = RouletteConfig::default().start().unwrap();
+            let result = tournament(players).run(&mut gun);
+            let eliminated: usize = result.rounds.iter().map(Vec::len).sum();
+            if players == 0 {
+                assert_eq!(result.champion, None);
+            } else {
+                assert!(result.champion.is_some());
+                assert_eq!(eliminated as u64, players - 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_draw() {
+        let config = RouletteConfig {
+            jam_probability: 1.0,
+            ..Default::default()
+        };
+        let mut gun = config.start().unwrap();
+        let result = tournament(3).run(&mut gun);
+        assert_eq!(result.rounds.len(), MAX_ROUNDS);
+        assert_eq!(result.champion, None);
+    }
+}
@@ -0,0 +1,898 @@
+//! Handling of incoming updates, shared by long polling and webhooks.
+
+use super::{
+    Api, CommandOutcome, Commands, Group, Triggers,
+    commands::{mute_user, render, topic_id, unmute_user},
+    constants::{INITIAL_BACKOFF, MAX_BACKOFF, MAX_SEND_RETRIES},
+    locale::{Locale, MessageKey},
+    ratelimit::{RateLimiter, retry_after},
+    state::StateWriter,
+};
+use frankenstein::{
+    AsyncTelegramApi, Error,
+    client_reqwest::Bot,
+    methods::{
+        DeleteMessageParams, PinChatMessageParams, SendMessageParams, SetMessageReactionParams,
+    },
+    types::{ChatType, Message, ReactionType, ReactionTypeEmoji, ReplyParameters},
+    updates::{Update, UpdateContent},
+};
+use log::{debug, error, info, warn};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tokio::{sync::Mutex, time::sleep};
+
+/// Everything needed to handle updates. Cheap to share behind an [`Arc`].
+pub struct Context<B = Bot> {
+    /// The bot.
+    pub bot: Arc<B>,
+    /// Username of the bot, for recognizing commands mentioning it.
+    pub username: String,
+    /// Trigger words of the commands, remapped on SIGHUP.
+    pub triggers: Arc<RwLock<Triggers>>,
+    /// Mapping whitelisted group ID to its state.
+    pub group_data: Arc<HashMap<i64, Mutex<Group>>>,
+    /// Writer of the state file, if persistence is enabled.
+    pub state_writer: Option<Arc<StateWriter>>,
+    /// Delete informational replies after this many seconds.
+    pub reply_ttl_secs: Option<u64>,
+    /// Whether to treat messages edited into commands as commands.
+    pub process_edits: bool,
+    /// Reply to messages in private chats, empty to disable.
+    pub private_reply: String,
+    /// Rate limiter of the replies.
+    pub limiter: RateLimiter,
+    /// Only log restrictions instead of carrying them out, marking the replies as such.
+    pub dry_run: bool,
+}
+
+/// Response to the message carrying a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// ID of the chat to respond in.
+    pub chat_id: i64,
+    /// ID of the topic to respond in, if any.
+    pub thread_id: Option<i32>,
+    /// ID of the message to reply to.
+    pub message_id: i32,
+    /// Whether the command is purely informational, thus its replies deleted after `reply_ttl_secs`.
+    pub informational: bool,
+    /// Actions to take, in order.
+    pub outcomes: Vec<CommandOutcome>,
+}
+
+impl Context {
+    /// Process an update, by handling it and applying the response, if any.
+    pub async fn process(self: Arc<Self>, update: Update) {
+        let Some(response) = self.handle_update(update).await else {
+            return;
+        };
+        let chat_id = response.chat_id;
+        let expiring = self.apply(response).await;
+        // Schedule deletion of informational replies, which is not waited for on shutdown
+        if let Some(ttl) = self.reply_ttl_secs {
+            for message_id in expiring {
+                let bot = self.bot.clone();
+                tokio::spawn(async move { delete_after(&bot, chat_id, message_id, ttl).await });
+            }
+        }
+    }
+}
+
+impl<B: Api> Context<B> {
+    /// Handle an update, by executing the command in it, returning the response to apply, if any.
+    pub async fn handle_update(&self, update: Update) -> Option<Response> {
+        if let Some(response) = self.private_response(&update) {
+            return Some(response);
+        }
+        let (command, arg, msg) = self.parse(update)?;
+        let chat_id = msg.chat.id;
+        let thread_id = topic_id(&msg);
+        let message_id = msg.message_id;
+        let group = &self.group_data[&chat_id];
+        if let Some(topic) = thread_id
+            && let Err(err) = group.lock().await.open_topic(topic)
+        {
+            error!("Failed to start the game of topic {topic} in group <{chat_id}>: {err}");
+            return None;
+        }
+        let outcomes = command.execute(self, msg, &arg, group).await;
+        if let Some(writer) = &self.state_writer {
+            writer.mark_dirty();
+        }
+        Some(Response {
+            chat_id,
+            thread_id,
+            message_id,
+            informational: command.is_informational(),
+            outcomes,
+        })
+    }
+
+    /// Apply the outcomes of the response in order, stopping at the first failed restriction.
+    ///
+    /// In a dry run, restrictions are only logged, and replies are prefixed with `[dry-run]`.
+    ///
+    /// Returns the IDs of the informational replies sent, to be deleted after `reply_ttl_secs`.
+    pub async fn apply(&self, response: Response) -> Vec<i32> {
+        let Response {
+            chat_id,
+            thread_id,
+            message_id,
+            informational,
+            outcomes,
+        } = response;
+        let mut expiring = Vec::new();
+        for outcome in outcomes {
+            let lasting = matches!(outcome, CommandOutcome::LastingReply(_));
+            match outcome {
+                CommandOutcome::Reply(text) | CommandOutcome::LastingReply(text) => {
+                    let text = if self.dry_run {
+                        format!("[dry-run] {text}")
+                    } else {
+                        text
+                    };
+                    let Some(sent) = self.reply_to(chat_id, thread_id, message_id, text).await
+                    else {
+                        continue;
+                    };
+                    if informational && !lasting {
+                        expiring.push(sent.message_id);
+                    }
+                }
+                CommandOutcome::Send(text) => {
+                    self.send_to(chat_id, thread_id, text).await;
+                }
+                CommandOutcome::SendPinned(text) => {
+                    let Some(sent) = self.send_to(chat_id, thread_id, text).await else {
+                        continue;
+                    };
+                    let pin_param = PinChatMessageParams::builder()
+                        .chat_id(chat_id)
+                        .message_id(sent.message_id)
+                        .build();
+                    if let Err(err) = self.bot.pin_chat_message(&pin_param).await {
+                        error!(
+                            "Failed to pin message {} in group <{chat_id}>: {err}",
+                            sent.message_id
+                        );
+                    }
+                }
+                CommandOutcome::React(emoji) if self.dry_run => {
+                    info!(
+                        "[dry-run] Not reacting with {emoji} to message {message_id} in <{chat_id}>"
+                    );
+                }
+                CommandOutcome::React(emoji) => {
+                    self.react(chat_id, message_id, emoji).await;
+                }
+                CommandOutcome::Restrict { name, until, .. } if self.dry_run => {
+                    info!("[dry-run] Not restricting user {name} in <{chat_id}> until {until}");
+                }
+                CommandOutcome::Restrict {
+                    user_id,
+                    name,
+                    until,
+                } => {
+                    // Later outcomes, such as announcing the mute, would be misleading
+                    if let Err(err) =
+                        mute_user(self.bot.as_ref(), chat_id, user_id, &name, until).await
+                    {
+                        error!("Failed to restrict user {name}: {err}");
+                        let key = restriction_failure(&err);
+                        let text = render(&self.message(chat_id, key).await, &[("name", &name)]);
+                        self.reply_to(chat_id, thread_id, message_id, text).await;
+                        break;
+                    }
+                }
+                CommandOutcome::Unrestrict { name, .. } if self.dry_run => {
+                    info!("[dry-run] Not unrestricting user {name} in <{chat_id}>");
+                }
+                CommandOutcome::Unrestrict { user_id, name } => {
+                    if let Err(err) = unmute_user(self.bot.as_ref(), chat_id, user_id, &name).await
+                    {
+                        error!("Failed to unrestrict user {name}: {err}");
+                        let text = render(
+                            &self.message(chat_id, MessageKey::PardonFailed).await,
+                            &[("name", &name)],
+                        );
+                        self.reply_to(chat_id, thread_id, message_id, text).await;
+                        break;
+                    }
+                }
+            }
+        }
+        expiring
+    }
+
+    /// Get the current trigger words of the commands.
+    pub fn triggers(&self) -> Triggers {
+        self.triggers.read().unwrap().clone()
+    }
+
+    /// Reply to the message in its topic right away, for commands announcing something before they
+    /// return, returning the reply if successful.
+    ///
+    /// Like replies among the outcomes, it is sent within the rate limit, and prefixed with
+    /// `[dry-run]` in a dry run.
+    pub async fn announce(&self, msg: &Message, text: String) -> Option<Message> {
+        let text = if self.dry_run {
+            format!("[dry-run] {text}")
+        } else {
+            text
+        };
+        self.reply_to(msg.chat.id, topic_id(msg), msg.message_id, text)
+            .await
+    }
+
+    /// Get the text for the key in the language of the group, or in the default language if the
+    /// group is not whitelisted.
+    async fn message(&self, chat_id: i64, key: MessageKey) -> String {
+        match self.group_data.get(&chat_id) {
+            Some(group) => group
+                .lock()
+                .await
+                .roulette()
+                .config()
+                .message(key)
+                .to_string(),
+            None => Locale::default().get(key).to_string(),
+        }
+    }
+
+    /// Send the standalone message to the topic of the chat, returning it if successful.
+    ///
+    /// In a dry run, the message is prefixed with `[dry-run]`.
+    async fn send_to(&self, chat_id: i64, thread_id: Option<i32>, text: String) -> Option<Message> {
+        let text = if self.dry_run {
+            format!("[dry-run] {text}")
+        } else {
+            text
+        };
+        let send_message_param = SendMessageParams::builder()
+            .chat_id(chat_id)
+            .maybe_message_thread_id(thread_id)
+            .text(text)
+            .build();
+        self.send(chat_id, &send_message_param).await
+    }
+
+    /// React to the message with the emoji, within the rate limit of the chat.
+    async fn react(&self, chat_id: i64, message_id: i32, emoji: String) {
+        let wait = self.limiter.acquire(chat_id);
+        if !wait.is_zero() {
+            debug!("Delaying reaction in group <{chat_id}> by {wait:?}");
+            sleep(wait).await;
+        }
+        let reaction = ReactionType::Emoji(ReactionTypeEmoji { emoji });
+        let reaction_param = SetMessageReactionParams::builder()
+            .chat_id(chat_id)
+            .message_id(message_id)
+            .reaction(vec![reaction])
+            .build();
+        if let Err(err) = self.bot.set_message_reaction(&reaction_param).await {
+            error!("Failed to react to message {message_id} in group <{chat_id}>: {err}");
+        }
+    }
+
+    /// Reply to the message with the text, returning the reply if successful.
+    async fn reply_to(
+        &self,
+        chat_id: i64,
+        thread_id: Option<i32>,
+        message_id: i32,
+        text: String,
+    ) -> Option<Message> {
+        let reply_param = ReplyParameters::builder().message_id(message_id).build();
+        let send_message_param = SendMessageParams::builder()
+            .chat_id(chat_id)
+            .maybe_message_thread_id(thread_id)
+            .text(text)
+            .reply_parameters(reply_param)
+            .build();
+        self.send(chat_id, &send_message_param).await
+    }
+
+    /// Send the message to the chat within its rate limit, returning it if successful.
+    ///
+    /// Messages rejected for flooding are retried after the delay Telegram asks for.
+    async fn send(&self, chat_id: i64, params: &SendMessageParams) -> Option<Message> {
+        let mut retries = 0;
+        loop {
+            let wait = self.limiter.acquire(chat_id);
+            if !wait.is_zero() {
+                debug!("Delaying message to group <{chat_id}> by {wait:?}");
+                sleep(wait).await;
+            }
+            let err = match self.bot.send_message(params).await {
+                Ok(res) => return Some(res.result),
+                Err(err) => err,
+            };
+            if let Some(delay) = retry_after(&err)
+                && retries < MAX_SEND_RETRIES
+            {
+                retries += 1;
+                warn!(
+                    "Flooding group <{chat_id}>, retrying in {}s",
+                    delay.as_secs()
+                );
+                sleep(delay).await;
+                continue;
+            }
+            error!("Failed to send message: {err}");
+            return None;
+        }
+    }
+
+    /// Get the response to a message in a private chat, telling that the bot only works in groups.
+    fn private_response(&self, update: &Update) -> Option<Response> {
+        let UpdateContent::Message(msg) = &update.content else {
+            return None;
+        };
+        if !matches!(msg.chat.type_field, ChatType::Private) || self.private_reply.is_empty() {
+            return None;
+        }
+        debug!("Received message from private chat: {msg:?}");
+        Some(Response {
+            chat_id: msg.chat.id,
+            thread_id: None,
+            message_id: msg.message_id,
+            informational: false,
+            outcomes: vec![CommandOutcome::Reply(self.private_reply.clone())],
+        })
+    }
+
+    /// Extract the command, its argument and the message from the update, if it is a command in a whitelisted group.
+    fn parse(&self, update: Update) -> Option<(Commands, String, Message)> {
+        debug!("Received update: {update:?}");
+        let msg = match update.content {
+            UpdateContent::Message(msg) => msg,
+            UpdateContent::EditedMessage(msg) if self.process_edits => msg,
+            UpdateContent::EditedMessage(msg) => {
+                // By default, edits never trigger commands, so that editing an old message into a command won't replay it
+                debug!("Ignoring edited message: {msg:?}");
+                return None;
+            }
+            _ => return None,
+        };
+        // Whitelist check
+        if !self.group_data.contains_key(&msg.chat.id) {
+            debug!("Received message from non-whitelisted chat: {msg:?}");
+            return None;
+        }
+
+        let text = msg.text.as_ref();
+        let triggers = self.triggers.read().unwrap();
+        let Some((command, arg)) = triggers.parse(text, &self.username) else {
+            debug!("Not a command: {text:?}");
+            return None;
+        };
+        let arg = arg.to_string();
+        Some((command, arg, msg))
+    }
+}
+
+/// Get the key of the reply explaining why the user could not be muted, instead of leaving them in
+/// silence.
+fn restriction_failure(err: &Error) -> MessageKey {
+    // E.g. "Bad Request: not enough rights to restrict/unrestrict chat member", after being demoted
+    let lacks_rights = matches!(
+        err,
+        Error::Api(response)
+            if response.error_code == 403 || response.description.contains("not enough rights")
+    );
+    if lacks_rights {
+        MessageKey::NoRights
+    } else {
+        MessageKey::MuteFailed
+    }
+}
+
+/// Get the delay before retrying a failed request, doubling the last delay if any, up to a cap.
+pub fn next_backoff(last: Option<Duration>) -> Duration {
+    match last {
+        Some(last) => (last * 2).min(MAX_BACKOFF),
+        None => INITIAL_BACKOFF,
+    }
+}
+
+/// Delete the given message after `ttl` seconds.
+///
+/// The timer lives in a detached task, so it is dropped along with the runtime on shutdown.
+async fn delete_after(bot: &Bot, chat_id: i64, message_id: i32, ttl: u64) {
+    sleep(Duration::from_secs(ttl)).await;
+    let delete_param = DeleteMessageParams::builder()
+        .chat_id(chat_id)
+        .message_id(message_id)
+        .build();
+    if let Err(err) = bot.delete_message(&delete_param).await {
+        error!("Failed to delete message {message_id} in group <{chat_id}>: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        RouletteConfig,
+        constants::MUTE_MARGIN_SECS,
+        group::DEFAULT_GUN,
+        mock::{self, MockApi},
+    };
+    use frankenstein::response::ErrorResponse;
+    use jiff::Timestamp;
+    use serde_json::json;
+
+    /// Create a context whitelisting group `-100`, without contacting Telegram.
+    fn context() -> Context {
+        context_with(Bot::new("token"), RouletteConfig::default())
+    }
+
+    /// Create a context whitelisting group `-100` playing with the config, talking to the given API.
+    fn context_with<B>(bot: B, config: RouletteConfig) -> Context<B> {
+        let group = Group::new(config.start().unwrap());
+        Context {
+            group_data: Arc::new(HashMap::from([(-100, Mutex::new(group))])),
+            ..mock::context(bot)
+        }
+    }
+
+    /// Construct an update of a message in the given supergroup.
+    fn update(chat_id: i64, text: &str) -> Update {
+        chat_update(chat_id, "supergroup", text)
+    }
+
+    /// Construct an update of a message in the given chat of the given type.
+    fn chat_update(chat_id: i64, chat_type: &str, text: &str) -> Update {
+        let json = format!(
+            r#"{{
+                "update_id": 1,
+                "message": {{
+                    "message_id": 2,
+                    "date": 0,
+                    "chat": {{"id": {chat_id}, "type": "{chat_type}"}},
+                    "from": {{"id": 3, "is_bot": false, "first_name": "Ferris"}},
+                    "text": "{text}"
+                }}
+            }}"#
+        );
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let context = context();
+        let (command, arg, msg) = context
+            .parse(update(-100, "/roulette@roulette_bot left"))
+            .unwrap();
+        assert!(matches!(command, Commands::Roulette));
+        assert_eq!(arg, "left");
+        assert_eq!(msg.message_id, 2);
+
+        // Not a command, or not in a whitelisted group
+        assert!(context.parse(update(-100, "hello")).is_none());
+        assert!(context.parse(update(-200, "/roulette")).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_remapped_triggers() {
+        let config = toml::from_str::<RouletteConfig>(r#"peek_mode = "next""#).unwrap();
+        let context = context_with(MockApi::default(), config);
+        let odds = async |text: &str| {
+            let response = context.handle_update(update(-100, text)).await?;
+            Some(response.outcomes)
+        };
+        let secret = |peek: &str| {
+            vec![CommandOutcome::Reply(format!(
+                "The odds are a secret here, but you may {peek} at the next chamber."
+            ))]
+        };
+        assert_eq!(odds("/odds").await, Some(secret("/peek")));
+
+        // Remapped as on SIGHUP, both in parsing and in the replies
+        let remapped = HashMap::from([
+            ("odds".to_string(), "chance".to_string()),
+            ("peek".to_string(), "look".to_string()),
+        ]);
+        *context.triggers.write().unwrap() = Triggers::new(&remapped).unwrap();
+        assert_eq!(odds("/odds").await, None);
+        assert_eq!(odds("/chance").await, Some(secret("/look")));
+    }
+
+    #[tokio::test]
+    async fn test_topics() {
+        let topic_update = |text: &str| {
+            let json = format!(
+                r#"{{
+                    "update_id": 1,
+                    "message": {{
+                        "message_id": 2,
+                        "date": 0,
+                        "chat": {{"id": -100, "type": "supergroup", "is_forum": true}},
+                        "from": {{"id": 3, "is_bot": false, "first_name": "Ferris"}},
+                        "message_thread_id": 5,
+                        "is_topic_message": true,
+                        "text": "{text}"
+                    }}
+                }}"#
+            );
+            serde_json::from_str::<Update>(&json).unwrap()
+        };
+        let config = toml::from_str::<RouletteConfig>("per_topic = true").unwrap();
+        let context = context_with(MockApi::default().with_member("member"), config);
+        let response = context.handle_update(topic_update("/spin")).await.unwrap();
+        assert_eq!(response.thread_id, Some(5));
+
+        // The topic has its own revolver, in the group's state
+        let group = context.group_data[&-100].lock().await;
+        assert_eq!(group.gun(Some(5), DEFAULT_GUN).unwrap().stats().fires, 0);
+        assert!(group.gun(Some(6), DEFAULT_GUN).is_some());
+        assert_eq!(group.state().topics.len(), 1);
+    }
+
+    #[test]
+    fn test_process_edits() {
+        let edited = || {
+            let json = r#"{
+                "update_id": 1,
+                "edited_message": {
+                    "message_id": 2,
+                    "date": 0,
+                    "edit_date": 1,
+                    "chat": {"id": -100, "type": "supergroup"},
+                    "from": {"id": 3, "is_bot": false, "first_name": "Ferris"},
+                    "text": "/peek"
+                }
+            }"#;
+            serde_json::from_str::<Update>(json).unwrap()
+        };
+        let mut context = context();
+        assert!(context.parse(edited()).is_none());
+
+        context.process_edits = true;
+        let (command, _, msg) = context.parse(edited()).unwrap();
+        assert!(matches!(command, Commands::Peek));
+        assert_eq!(msg.message_id, 2);
+    }
+
+    #[test]
+    fn test_restriction_failure() {
+        let api_error = |error_code, description: &str| {
+            Error::Api(ErrorResponse {
+                ok: false,
+                description: description.to_string(),
+                error_code,
+                parameters: None,
+            })
+        };
+        let demoted = api_error(
+            400,
+            "Bad Request: not enough rights to restrict/unrestrict chat member",
+        );
+        assert_eq!(restriction_failure(&demoted), MessageKey::NoRights);
+        let kicked = api_error(403, "Forbidden: bot was kicked from the supergroup chat");
+        assert_eq!(restriction_failure(&kicked), MessageKey::NoRights);
+
+        let other = api_error(400, "Bad Request: user not found");
+        assert_eq!(restriction_failure(&other), MessageKey::MuteFailed);
+    }
+
+    #[test]
+    fn test_next_backoff() {
+        let mut delays = Vec::new();
+        let mut delay = None;
+        for _ in 0..8 {
+            delay = Some(next_backoff(delay));
+            delays.push(delay.unwrap().as_secs());
+        }
+        assert_eq!(delays, [1, 2, 4, 8, 16, 32, 60, 60]);
+    }
+
+    #[tokio::test]
+    async fn test_handle_update() {
+        // Ignored without executing the command, thus without contacting Telegram
+        let response = context().handle_update(update(-200, "/roulette")).await;
+        assert_eq!(response, None);
+
+        // Told that the bot only works in groups
+        let private = chat_update(3, "private", "/start");
+        let response = context().handle_update(private.clone()).await;
+        assert_eq!(
+            response,
+            Some(Response {
+                chat_id: 3,
+                thread_id: None,
+                message_id: 2,
+                informational: false,
+                outcomes: vec![CommandOutcome::Reply("Groups only".to_string())],
+            })
+        );
+        let silent = Context {
+            private_reply: String::new(),
+            ..context()
+        };
+        assert_eq!(silent.handle_update(private).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_bullet_restricts_once() {
+        // The only chamber is loaded, so the first pull is a bullet
+        let config = RouletteConfig::builder()
+            .chambers(1)
+            .bullets(1)
+            .jam_probability(0.0)
+            .min_mute_time(60)
+            .max_mute_time(60)
+            .build()
+            .unwrap();
+        let api = MockApi::default()
+            .with_member("member")
+            .with("restrictChatMember", json!(true))
+            .with_sending();
+        let context = context_with(api, config);
+
+        let start = Timestamp::now().as_second() as u64;
+        let response = context
+            .handle_update(update(-100, "/roulette"))
+            .await
+            .unwrap();
+        context.apply(response).await;
+        let end = Timestamp::now().as_second() as u64;
+
+        let api = &context.bot;
+        assert_eq!(
+            api.methods(),
+            ["getChatMember", "restrictChatMember", "sendMessage"]
+        );
+        let restrictions = api.requests("restrictChatMember");
+        let [restriction] = &restrictions[..] else {
+            panic!("Expected exactly one restriction: {restrictions:?}");
+        };
+        assert_eq!(restriction["chat_id"], -100);
+        assert_eq!(restriction["user_id"], 3);
+        let until = restriction["until_date"].as_u64().unwrap();
+        let margin = 60 + MUTE_MARGIN_SECS;
+        assert!((start + margin..=end + margin).contains(&until));
+    }
+
+    #[tokio::test]
+    async fn test_restriction_failure_reply() {
+        let config = RouletteConfig::builder()
+            .chambers(1)
+            .bullets(1)
+            .jam_probability(0.0)
+            .build()
+            .unwrap();
+        // Demoted after startup
+        let api = MockApi::default()
+            .with_member("member")
+            .failing(
+                "restrictChatMember",
+                400,
+                "Bad Request: not enough rights to restrict/unrestrict chat member",
+            )
+            .with_sending();
+        let context = context_with(api, config);
+
+        let response = context
+            .handle_update(update(-100, "/roulette"))
+            .await
+            .unwrap();
+        context.apply(response).await;
+
+        // Told why nobody got muted, instead of announcing the shot
+        let api = &context.bot;
+        assert_eq!(
+            api.methods(),
+            ["getChatMember", "restrictChatMember", "sendMessage"]
+        );
+        assert_eq!(
+            api.requests("sendMessage")[0]["text"],
+            "I can no longer mute people here. Please grant me the permission to ban users."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unrestriction_failure_reply() {
+        let config = RouletteConfig {
+            language: Locale::Ru,
+            ..Default::default()
+        };
+        let api = MockApi::default()
+            .failing("restrictChatMember", 400, "Bad Request: user not found")
+            .with_sending();
+        let context = context_with(api, config);
+        let response = Response {
+            chat_id: -100,
+            thread_id: None,
+            message_id: 2,
+            informational: false,
+            outcomes: vec![CommandOutcome::Unrestrict {
+                user_id: 3,
+                name: "Ferris".to_string(),
+            }],
+        };
+        context.apply(response).await;
+
+        // In the language of the group
+        assert_eq!(
+            context.bot.requests("sendMessage")[0]["text"],
+            "Не удалось помиловать Ferris."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lasting_reply() {
+        let api = MockApi::default().with_sending();
+        let context = context_with(api, RouletteConfig::default());
+        let response = Response {
+            chat_id: -100,
+            thread_id: None,
+            message_id: 2,
+            informational: true,
+            outcomes: vec![CommandOutcome::Reply("Gone soon".to_string())],
+        };
+        assert_eq!(context.apply(response.clone()).await, [4]);
+
+        // Kept despite the command being informational
+        let response = Response {
+            outcomes: vec![CommandOutcome::LastingReply("Here to stay".to_string())],
+            ..response
+        };
+        assert!(context.apply(response).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_announce() {
+        let context = Context {
+            dry_run: true,
+            ..context_with(MockApi::default().with_sending(), RouletteConfig::default())
+        };
+        let msg = serde_json::from_value(json!({
+            "message_id": 2,
+            "date": 0,
+            "chat": {"id": -100, "type": "supergroup", "is_forum": true},
+            "message_thread_id": 5,
+            "is_topic_message": true
+        }))
+        .unwrap();
+        assert!(
+            context
+                .announce(&msg, "Hold on".to_string())
+                .await
+                .is_some()
+        );
+
+        // Replied to in the topic, marked like the outcomes
+        let sent = &context.bot.requests("sendMessage")[0];
+        assert_eq!(sent["text"], "[dry-run] Hold on");
+        assert_eq!(sent["message_thread_id"], 5);
+        assert_eq!(sent["reply_parameters"]["message_id"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_send_pinned() {
+        let api = MockApi::default()
+            .with_sending()
+            .with("pinChatMessage", json!(true));
+        let context = context_with(api, RouletteConfig::default());
+        let response = Response {
+            chat_id: -100,
+            thread_id: Some(5),
+            message_id: 2,
+            informational: false,
+            outcomes: vec![CommandOutcome::SendPinned("Jackpot!".to_string())],
+        };
+        context.apply(response).await;
+
+        // Sent to the topic, then pinned
+        let api = &context.bot;
+        assert_eq!(api.methods(), ["sendMessage", "pinChatMessage"]);
+        assert_eq!(api.requests("sendMessage")[0]["message_thread_id"], 5);
+        assert_eq!(api.requests("pinChatMessage")[0]["message_id"], 4);
+    }
+
+    #[tokio::test]
+    async fn test_react() {
+        let response = Response {
+            chat_id: -100,
+            thread_id: None,
+            message_id: 2,
+            informational: false,
+            outcomes: vec![CommandOutcome::React("🔥".to_string())],
+        };
+        let api = MockApi::default().with("setMessageReaction", json!(true));
+        let context = context_with(api, RouletteConfig::default());
+        context.apply(response.clone()).await;
+        let reactions = context.bot.requests("setMessageReaction");
+        assert_eq!(reactions[0]["message_id"], 2);
+        assert_eq!(reactions[0]["reaction"][0]["emoji"], "🔥");
+
+        // Only logged in a dry run, as a reaction can't be marked as such
+        let context = Context {
+            dry_run: true,
+            ..context_with(MockApi::default(), RouletteConfig::default())
+        };
+        context.apply(response).await;
+        assert!(context.bot.methods().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_tournament() {
+        // The first player to pull draws the only bullet
+        let config = RouletteConfig {
+            chambers: 1,
+            bullets: 1,
+            jam_probability: 0.0,
+            tournament_join_secs: 1,
+            ..Default::default()
+        };
+        let api = MockApi::default().with_member("creator").with_sending();
+        let context = Context {
+            dry_run: true,
+            ..context_with(api, config)
+        };
+
+        let (response, ()) =
+            tokio::join!(context.handle_update(update(-100, "/tournament")), async {
+                sleep(Duration::from_millis(100)).await;
+                let mut group = context.group_data[&-100].lock().await;
+                let tournament = group.tournament().as_mut().unwrap();
+                tournament.join(1, "Ferris".to_string()).unwrap();
+                tournament.join(2, "Corro".to_string()).unwrap();
+            });
+        context.apply(response.unwrap()).await;
+
+        // The results are announced, but nobody gets restricted
+        let api = &context.bot;
+        assert_eq!(
+            api.methods(),
+            ["getChatMember", "sendMessage", "sendMessage"]
+        );
+        let sent = api.requests("sendMessage");
+        assert_eq!(
+            sent[0]["text"],
+            "[dry-run] A tournament has started! Send /join within 1s to take part."
+        );
+        assert_eq!(
+            sent[1]["text"],
+            "[dry-run] Round 1: Ferris got shot.\nCorro is the champion!"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run() {
+        let config = RouletteConfig::builder()
+            .chambers(1)
+            .bullets(1)
+            .jam_probability(0.0)
+            .build()
+            .unwrap();
+        let api = MockApi::default().with_member("member").with_sending();
+        let context = Context {
+            dry_run: true,
+            ..context_with(api, config)
+        };
+
+        let response = context
+            .handle_update(update(-100, "/roulette"))
+            .await
+            .unwrap();
+        context.apply(response).await;
+
+        // The shot is announced, but nobody gets restricted
+        let api = &context.bot;
+        assert_eq!(api.methods(), ["getChatMember", "sendMessage"]);
+        let text = api.requests("sendMessage")[0]["text"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(
+            text.starts_with("[dry-run] Bang! Ferris was shot"),
+            "{text}"
+        );
+    }
+}